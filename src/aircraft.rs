@@ -0,0 +1,188 @@
+use crate::airport::GateSize;
+use crate::flight::Performance;
+use serde::Deserialize;
+use std::fmt;
+use std::path::Path;
+
+// --- AIRCRAFT MODULE ---
+// Performance data for each aircraft type: wake category, gate size, speeds,
+// climb and descent rates, runway length, crosswind limit and fuel burn.
+// Loaded from data/aircraft.toml, or a file given with --aircraft to add types.
+
+/// The types used when no aircraft file is given
+const DEFAULT_AIRCRAFT: &str = include_str!("../data/aircraft.toml");
+
+/// Wake turbulence category
+#[derive(Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WeightClass {
+    Light,
+    Medium,
+    Heavy,
+}
+
+/// Everything the simulation needs to know about one aircraft type
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct AircraftType {
+    /// ICAO type designator, e.g. B737
+    #[serde(rename = "type")]
+    pub designator: String,
+    pub wake: WeightClass,
+    /// Smallest gate the type fits at
+    pub gate: GateSize,
+    /// Flown while established and waiting to land
+    pub approach_speed_kt: u16,
+    pub climb_fpm: f64,
+    pub descent_fpm: f64,
+    pub min_speed_kt: u16,
+    pub max_speed_kt: u16,
+    pub ceiling_ft: u32,
+    /// Shortest runway the type can take off from or land on
+    pub runway_length_ft: u32,
+    /// Maximum demonstrated crosswind, in knots
+    pub crosswind_limit_kt: u16,
+    /// Pounds of fuel burned per minute in cruise or holding
+    pub fuel_burn_lb_per_min: f64,
+}
+
+impl AircraftType {
+    /// A type missing from the database: assume something small and slow,
+    /// and be conservative with its limits
+    pub fn unknown(designator: &str) -> Self {
+        AircraftType {
+            designator: designator.to_string(),
+            wake: WeightClass::Light,
+            gate: GateSize::Regional,
+            approach_speed_kt: 120,
+            climb_fpm: 1500.0,
+            descent_fpm: 1500.0,
+            min_speed_kt: 150,
+            max_speed_kt: 280,
+            ceiling_ft: 25000,
+            runway_length_ft: 4000,
+            crosswind_limit_kt: 25,
+            fuel_burn_lb_per_min: 20.0,
+        }
+    }
+
+    /// Climb, descent, speed and ceiling, for flying the type
+    pub fn performance(&self) -> Performance {
+        Performance {
+            climb_fpm: self.climb_fpm,
+            descent_fpm: self.descent_fpm,
+            min_speed_kt: self.min_speed_kt,
+            max_speed_kt: self.max_speed_kt,
+            ceiling_ft: self.ceiling_ft,
+        }
+    }
+}
+
+/// Every aircraft type in service
+#[derive(Debug, Clone, Deserialize)]
+pub struct AircraftDatabase {
+    types: Vec<AircraftType>,
+}
+
+/// Why an aircraft file could not be loaded
+#[derive(Debug)]
+pub enum AircraftError {
+    Io(std::io::Error),
+    Parse(toml::de::Error),
+    Invalid(String),
+}
+
+impl Default for AircraftDatabase {
+    fn default() -> Self {
+        AircraftDatabase::new()
+    }
+}
+
+impl AircraftDatabase {
+    /// The built-in types
+    pub fn new() -> Self {
+        AircraftDatabase::from_toml(DEFAULT_AIRCRAFT).expect("built-in aircraft table is valid")
+    }
+
+    /// Load the aircraft table from a TOML file
+    pub fn load(path: impl AsRef<Path>) -> Result<AircraftDatabase, AircraftError> {
+        let source = std::fs::read_to_string(path).map_err(AircraftError::Io)?;
+        AircraftDatabase::from_toml(&source)
+    }
+
+    pub fn from_toml(source: &str) -> Result<AircraftDatabase, AircraftError> {
+        let database: AircraftDatabase = toml::from_str(source).map_err(AircraftError::Parse)?;
+
+        if database.types.is_empty() {
+            return Err(AircraftError::Invalid("no aircraft types".to_string()));
+        }
+        for (i, aircraft) in database.types.iter().enumerate() {
+            if database.types[..i].iter().any(|t| t.designator.eq_ignore_ascii_case(&aircraft.designator)) {
+                return Err(AircraftError::Invalid(format!("type {} is listed twice", aircraft.designator)));
+            }
+            if aircraft.min_speed_kt > aircraft.max_speed_kt {
+                return Err(AircraftError::Invalid(format!(
+                    "type {} has min speed {} kt above max speed {} kt",
+                    aircraft.designator, aircraft.min_speed_kt, aircraft.max_speed_kt
+                )));
+            }
+            if aircraft.climb_fpm <= 0.0 || aircraft.descent_fpm <= 0.0 || aircraft.fuel_burn_lb_per_min <= 0.0 {
+                return Err(AircraftError::Invalid(format!(
+                    "type {} needs positive climb, descent and fuel burn rates",
+                    aircraft.designator
+                )));
+            }
+        }
+        Ok(database)
+    }
+
+    pub fn get(&self, designator: &str) -> Option<&AircraftType> {
+        self.types.iter().find(|t| t.designator.eq_ignore_ascii_case(designator))
+    }
+
+    /// The entry for `designator`, or a conservative guess if it is missing
+    pub fn lookup(&self, designator: &str) -> AircraftType {
+        self.get(designator)
+            .cloned()
+            .unwrap_or_else(|| AircraftType::unknown(designator))
+    }
+
+    /// Designators of every type, in file order
+    pub fn designators(&self) -> Vec<&str> {
+        self.types.iter().map(|t| t.designator.as_str()).collect()
+    }
+
+    /// A short hash of every type and its performance, e.g. "9f3a61c04b2d8e17".
+    /// Two tables with the same entries in the same order share it, however
+    /// their files are laid out.
+    pub fn fingerprint(&self) -> String {
+        // FNV-1a, which unlike std's hasher is the same on every build
+        let hash = format!("{:?}", self.types)
+            .bytes()
+            .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+            });
+        format!("{:016x}", hash)
+    }
+}
+
+impl fmt::Display for WeightClass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WeightClass::Light => write!(f, "light"),
+            WeightClass::Medium => write!(f, "medium"),
+            WeightClass::Heavy => write!(f, "heavy"),
+        }
+    }
+}
+
+impl fmt::Display for AircraftError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AircraftError::Io(e) => write!(f, "could not read aircraft file: {}", e),
+            AircraftError::Parse(e) => write!(f, "invalid aircraft file: {}", e),
+            AircraftError::Invalid(message) => write!(f, "invalid aircraft table: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for AircraftError {}
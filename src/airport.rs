@@ -1,73 +1,802 @@
-use rand::seq::SliceRandom; // <-- Removed 'Rng'
+use crate::event::SimEvent;
+use crate::ground::GroundLayout;
+use crate::metar::{Metar, MetarError, ObservationTime, Taf};
+use crate::navdata::NavDatabase;
+use crate::aircraft::WeightClass;
+use crate::separation::{self, IncursionSeverity};
+use crate::weather::WeatherSystem;
+use rand::Rng;
+use serde::Deserialize;
+use std::fmt;
+use std::path::Path;
 
 // --- AIRPORT MODULE ---
 // Contains logic for the airport environment (weather, runways)
+// Layouts are described in TOML files under data/airports/.
 
+/// The layout used when no airport file is given
+const DEFAULT_AIRPORT: &str = include_str!("../data/airports/kpdx.toml");
+
+/// Wind as reported: the direction it blows FROM, in degrees, and its speed
 #[derive(Debug, PartialEq, Clone, Copy)]
-pub enum Weather {
-    Clear,
-    Rainy,
-    HighWinds,
+pub struct Wind {
+    /// 1-360 degrees true; None when the wind is calm or variable (VRB)
+    pub direction: Option<u16>,
+    pub speed_kt: u16,
+    pub gust_kt: Option<u16>,
 }
 
-#[derive(Debug)]
+impl Wind {
+    pub fn calm() -> Self {
+        Wind {
+            direction: None,
+            speed_kt: 0,
+            gust_kt: None,
+        }
+    }
+
+    /// The speed to check limits against: the gust if there is one
+    pub fn peak_kt(&self) -> u16 {
+        self.gust_kt.unwrap_or(self.speed_kt).max(self.speed_kt)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Weather {
+    pub wind: Wind,
+    pub rain: bool,
+    /// Prevailing visibility in statute miles
+    pub visibility_sm: f64,
+    /// Height of the lowest broken or overcast layer above the field, in
+    /// feet. None when there is no ceiling.
+    pub ceiling_ft: Option<u32>,
+}
+
+/// Flight category, from ceiling and visibility, as shown on weather charts
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum FlightCategory {
+    /// Low IFR: ceiling below 500 ft or visibility below 1 SM
+    Lifr,
+    /// IFR: ceiling below 1000 ft or visibility below 3 SM
+    Ifr,
+    /// Marginal VFR: ceiling 3000 ft or less, or visibility 5 SM or less
+    Mvfr,
+    Vfr,
+}
+
+/// The worst ceiling and visibility an operation is allowed in
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Minimums {
+    pub ceiling_ft: u32,
+    pub visibility_sm: f64,
+}
+
+impl Weather {
+    pub fn clear() -> Self {
+        Weather {
+            wind: Wind::calm(),
+            rain: false,
+            visibility_sm: 10.0,
+            ceiling_ft: None,
+        }
+    }
+
+    pub fn category(&self) -> FlightCategory {
+        let ceiling = self.ceiling_ft.unwrap_or(u32::MAX);
+        let visibility = self.visibility_sm;
+        if ceiling < 500 || visibility < 1.0 {
+            FlightCategory::Lifr
+        } else if ceiling < 1000 || visibility < 3.0 {
+            FlightCategory::Ifr
+        } else if ceiling <= 3000 || visibility <= 5.0 {
+            FlightCategory::Mvfr
+        } else {
+            FlightCategory::Vfr
+        }
+    }
+
+    /// Whether `self` differs enough from `previous` to report: a change of
+    /// flight category, rain starting or stopping, or a marked wind shift
+    pub fn is_significant_change(&self, previous: &Weather) -> bool {
+        let (wind, before) = (&self.wind, &previous.wind);
+        let shifted = match (wind.direction, before.direction) {
+            (Some(direction), Some(was)) => {
+                let turn = (direction as i32 - was as i32).rem_euclid(360);
+                turn.min(360 - turn) >= 30
+            }
+            // Settling down or becoming variable
+            (direction, was) => direction != was,
+        };
+        let turned = shifted && wind.speed_kt.max(before.speed_kt) >= 6;
+
+        self.category() != previous.category()
+            || self.rain != previous.rain
+            || turned
+            || wind.speed_kt.abs_diff(before.speed_kt) >= 10
+            || wind.gust_kt.is_some() != before.gust_kt.is_some()
+    }
+
+    /// Whether ceiling and visibility are at or above `minimums`
+    pub fn meets(&self, minimums: Minimums) -> bool {
+        self.ceiling_ft.unwrap_or(u32::MAX) >= minimums.ceiling_ft
+            && self.visibility_sm >= minimums.visibility_sm
+    }
+}
+
+/// Wind resolved along and across a runway, in knots
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct WindComponents {
+    /// Positive is a headwind, negative a tailwind
+    pub headwind: f64,
+    /// Positive blows from the right, negative from the left
+    pub crosswind: f64,
+}
+
+impl WindComponents {
+    pub fn tailwind(&self) -> f64 {
+        (-self.headwind).max(0.0)
+    }
+}
+
+/// What a runway is being used for
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum RunwayOperation {
+    Takeoff,
+    Landing,
+}
+
+/// Why a runway was closed
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ClosureReason {
+    /// Visibility is below the runway's takeoff minimum
+    LowVisibility,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Surface {
+    Asphalt,
+    Concrete,
+    Grass,
+    Gravel,
+}
+
+/// Who is on a runway, and until when
+#[derive(Debug, Clone, PartialEq)]
+pub struct Occupancy {
+    pub plane: String,
+    /// First tick the runway is free again; `None` while lined up and waiting
+    pub until: Option<u64>,
+}
+
+/// The last takeoff or landing on a runway
+#[derive(Debug, Clone, PartialEq)]
+pub struct Movement {
+    pub plane: String,
+    pub tick: u64,
+    pub weight_class: WeightClass,
+    pub operation: RunwayOperation,
+}
+
+#[derive(Debug, Deserialize)]
 pub struct Runway {
     pub id: String,
+    /// Magnetic heading in degrees
+    pub heading: u16,
+    pub length_ft: u32,
+    /// Where the runway starts, in feet from the terminal (x east, y north)
+    pub threshold_x_ft: f64,
+    pub threshold_y_ft: f64,
+    pub surface: Surface,
+    /// Whether the runway has an instrument landing system
+    pub ils: bool,
+    #[serde(default)]
+    pub missed_approach: MissedApproach,
+    #[serde(skip_deserializing, default = "open_by_default")]
     pub is_open: bool,
+    #[serde(skip)]
+    pub occupancy: Option<Occupancy>,
+    /// Used for wake turbulence spacing
+    #[serde(skip)]
+    pub last_movement: Option<Movement>,
+    /// A taxiing plane on, or cleared across, the runway
+    #[serde(skip)]
+    pub crossing: Option<String>,
+    /// A plane cleared to land, on final for the runway
+    #[serde(skip)]
+    pub on_final: Option<String>,
+}
+
+fn open_by_default() -> bool {
+    true
 }
 
+/// What a plane going around from a runway flies: climb to an altitude,
+/// direct to a fix if there is one, then rejoin the arrivals
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MissedApproach {
+    pub altitude_ft: u32,
+    #[serde(default)]
+    pub fix: Option<String>,
+}
+
+impl Default for MissedApproach {
+    fn default() -> Self {
+        MissedApproach {
+            altitude_ft: 3000,
+            fix: None,
+        }
+    }
+}
+
+/// The largest aircraft a gate can take
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GateSize {
+    /// Regional jets and smaller
+    Regional,
+    Narrowbody,
+    Widebody,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Gate {
+    pub id: String,
+    #[serde(default = "widebody_by_default")]
+    pub size: GateSize,
+    /// The plane parked at, or assigned to, the gate
+    #[serde(skip)]
+    pub occupant: Option<String>,
+}
+
+fn widebody_by_default() -> GateSize {
+    GateSize::Widebody
+}
+
+impl Gate {
+    /// Whether an aircraft of `size` fits at the gate
+    pub fn fits(&self, size: GateSize) -> bool {
+        size <= self.size
+    }
+
+    pub fn is_free(&self) -> bool {
+        self.occupant.is_none()
+    }
+}
+
+/// A named point in the airspace, placed by bearing and range from the field
+#[derive(Debug, Clone, Deserialize)]
+pub struct Fix {
+    pub name: String,
+    /// Degrees true from the field
+    pub bearing: u16,
+    pub range_nm: f64,
+    /// The holding pattern published over the fix, if there is one
+    #[serde(default)]
+    pub hold: Option<Hold>,
+}
+
+/// Which way a holding pattern turns
+#[derive(Debug, PartialEq, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Turn {
+    #[default]
+    Right,
+    Left,
+}
+
+/// A published holding pattern: a racetrack flown inbound to the fix on a
+/// course, with planes stacked 1000 ft apart from the lowest level up
+#[derive(Debug, Clone, Deserialize)]
+pub struct Hold {
+    /// Course flown toward the fix, degrees
+    pub inbound_course: u16,
+    #[serde(default)]
+    pub turn: Turn,
+    pub lowest_altitude_ft: u32,
+    /// Number of levels in the stack
+    pub levels: u32,
+}
+
+impl Hold {
+    /// Altitude of `level`, counting up from 0 at the bottom of the stack
+    pub fn altitude_ft(&self, level: u32) -> u32 {
+        self.lowest_altitude_ft + level * 1000
+    }
+}
+
+impl Fix {
+    /// Position in nautical miles from the field (x east, y north)
+    pub fn position(&self) -> (f64, f64) {
+        let radians = f64::from(self.bearing).to_radians();
+        (self.range_nm * radians.sin(), self.range_nm * radians.cos())
+    }
+}
+
+#[derive(Deserialize)]
 pub struct Airport {
+    pub icao: String,
+    pub name: String,
+    pub elevation_ft: i32,
+    /// Direction (degrees) the wind usually blows from at this field
+    pub prevailing_wind: u16,
+    #[serde(skip_deserializing, default = "Weather::clear")]
     pub weather: Weather,
+    /// Fronts driving the weather, and the forecast
+    #[serde(skip)]
+    pub weather_system: WeatherSystem,
+    /// Weather as last reported; small drifts aren't announced
+    #[serde(skip_deserializing, default = "Weather::clear")]
+    reported: Weather,
     pub runways: Vec<Runway>,
+    #[serde(default)]
+    pub gates: Vec<Gate>,
+    /// Fixes planes can be sent direct to
+    #[serde(default)]
+    pub fixes: Vec<Fix>,
+    /// Taxiways joining the gates and runways
+    #[serde(default)]
+    pub ground: GroundLayout,
+}
+
+/// Why an airport definition could not be loaded
+#[derive(Debug)]
+pub enum AirportError {
+    Io(std::io::Error),
+    Parse(toml::de::Error),
+    Invalid(String),
+}
+
+impl Default for Airport {
+    fn default() -> Self {
+        Airport::new()
+    }
 }
 
 impl Airport {
+    /// The built-in KPDX layout, which the built-in navigation database
+    /// always has
     pub fn new() -> Self {
-        Airport {
-            weather: Weather::Clear,
-            runways: vec![
-                Runway { id: "24L".to_string(), is_open: true },
-                Runway { id: "24R".to_string(), is_open: true },
-                Runway { id: "18".to_string(), is_open: true },
-            ],
+        Airport::parse(DEFAULT_AIRPORT).expect("built-in airport definition is valid")
+    }
+
+    /// Load an airport definition from a TOML file, checking it against the
+    /// session's navigation database
+    pub fn load(path: impl AsRef<Path>, navdata: &NavDatabase) -> Result<Airport, AirportError> {
+        let source = std::fs::read_to_string(path).map_err(AirportError::Io)?;
+        Airport::from_toml(&source, navdata)
+    }
+
+    pub fn from_toml(source: &str, navdata: &NavDatabase) -> Result<Airport, AirportError> {
+        let airport = Airport::parse(source)?;
+        // Flights to and from the airport are routed from the navigation database
+        if navdata.airport(&airport.icao).is_none() {
+            return Err(AirportError::Invalid(format!(
+                "{} is not in the navigation database",
+                airport.icao
+            )));
         }
+        Ok(airport)
     }
 
-    /// Update weather and runway status
-    pub fn update_weather(&mut self) {
-        let patterns = [Weather::Clear, Weather::Rainy, Weather::HighWinds];
-        self.weather = *patterns.choose(&mut rand::thread_rng()).unwrap();
+    /// Parse a layout and check it hangs together
+    fn parse(source: &str) -> Result<Airport, AirportError> {
+        let airport: Airport = toml::from_str(source).map_err(AirportError::Parse)?;
 
-        println!("WEATHER UPDATE: Weather is now {:?}", self.weather);
+        if airport.runways.is_empty() {
+            return Err(AirportError::Invalid(format!("{} has no runways", airport.icao)));
+        }
+        for (i, runway) in airport.runways.iter().enumerate() {
+            if runway.heading == 0 || runway.heading > 360 {
+                return Err(AirportError::Invalid(format!(
+                    "runway {} has heading {}, expected 1-360",
+                    runway.id, runway.heading
+                )));
+            }
+            if airport.runways[..i].iter().any(|r| r.id.eq_ignore_ascii_case(&runway.id)) {
+                return Err(AirportError::Invalid(format!("runway {} is listed twice", runway.id)));
+            }
+            if let Some(fix) = &runway.missed_approach.fix
+                && airport.get_fix(fix).is_none()
+            {
+                return Err(AirportError::Invalid(format!(
+                    "runway {}'s missed approach goes to unknown fix {}",
+                    runway.id, fix
+                )));
+            }
+        }
+        if airport.gates.is_empty() {
+            return Err(AirportError::Invalid(format!("{} has no gates", airport.icao)));
+        }
+        airport
+            .ground
+            .validate(&airport.runways, &airport.gates)
+            .map_err(AirportError::Invalid)?;
+        for (i, fix) in airport.fixes.iter().enumerate() {
+            if fix.bearing > 360 {
+                return Err(AirportError::Invalid(format!(
+                    "fix {} has bearing {}, expected 0-360",
+                    fix.name, fix.bearing
+                )));
+            }
+            if airport.fixes[..i].iter().any(|f| f.name.eq_ignore_ascii_case(&fix.name)) {
+                return Err(AirportError::Invalid(format!("fix {} is listed twice", fix.name)));
+            }
+            if let Some(hold) = &fix.hold
+                && (hold.inbound_course == 0 || hold.inbound_course > 360 || hold.levels == 0)
+            {
+                return Err(AirportError::Invalid(format!(
+                    "hold at {} needs an inbound course of 1-360 and at least one level",
+                    fix.name
+                )));
+            }
+        }
+        Ok(airport)
+    }
+
+    /// Plan the first fronts so there is a forecast before the first tick
+    pub fn plan_weather(&mut self, rng: &mut impl Rng, tick: u64) {
+        self.weather_system.plan(self.prevailing_wind, tick, rng);
+    }
 
-        // Apply weather-based rules
+    /// Update weather and runway status. The weather drifts a little every
+    /// tick; a report goes out only when it has changed significantly.
+    pub fn update_weather(&mut self, rng: &mut impl Rng, tick: u64, events: &mut Vec<SimEvent>) {
+        self.weather = self
+            .weather_system
+            .step(&self.weather, self.prevailing_wind, tick, rng);
+
+        if self.weather.is_significant_change(&self.reported) {
+            self.reported = self.weather;
+            events.push(SimEvent::WeatherChanged(self.weather));
+        }
+        self.apply_weather(events);
+    }
+
+    /// Set the current weather outright and open or close runways to match
+    pub fn set_weather(&mut self, weather: Weather, events: &mut Vec<SimEvent>) {
+        self.weather = weather;
+        self.reported = weather;
+        events.push(SimEvent::WeatherChanged(self.weather));
+        self.apply_weather(events);
+    }
+
+    /// Apply weather-based rules. Wind doesn't close runways outright; each
+    /// clearance checks the components against the aircraft's limits, and
+    /// landings check the approach minimums.
+    fn apply_weather(&mut self, events: &mut Vec<SimEvent>) {
         for runway in self.runways.iter_mut() {
-            match self.weather {
-                Weather::Clear => runway.is_open = true,
-                Weather::Rainy => {
-                    // In rain, let's close one runway
-                    if runway.id == "18" {
-                        runway.is_open = false;
-                        println!("RUNWAY INFO: Runway 18 closed due to rain.");
-                    } else {
-                        runway.is_open = true;
-                    }
-                }
-                Weather::HighWinds => {
-                    // In high winds, close crosswind runways
-                    if runway.id.contains("24") {
-                        runway.is_open = false;
-                        println!("RUNWAY INFO: Runways 24L/R closed due to high winds.");
-                    } else {
-                        runway.is_open = true;
-                    }
-                }
+            let was_open = runway.is_open;
+            runway.is_open = self.weather.visibility_sm >= runway.takeoff_visibility_sm();
+
+            if was_open && !runway.is_open {
+                events.push(SimEvent::RunwayClosed {
+                    runway: runway.id.clone(),
+                    reason: ClosureReason::LowVisibility,
+                });
+            } else if !was_open && runway.is_open {
+                events.push(SimEvent::RunwayReopened {
+                    runway: runway.id.clone(),
+                });
             }
         }
     }
 
+    /// The current weather as a METAR observed at `time`
+    pub fn metar(&self, time: ObservationTime) -> Metar {
+        Metar::from_weather(&self.icao, time, &self.weather)
+    }
+
+    /// The forecast from `tick`, as a TAF
+    pub fn taf(&self, tick: u64) -> Taf {
+        let periods = self
+            .weather_system
+            .forecast()
+            .map(|front| {
+                let starts_at = front.starts_at.max(tick);
+                (ObservationTime::at_tick(starts_at), front.weather)
+            })
+            .collect();
+        Taf {
+            station: self.icao.clone(),
+            issued: ObservationTime::at_tick(tick),
+            periods,
+        }
+    }
+
+    /// Set the weather from a METAR. Reports from other stations are
+    /// accepted, so real conditions elsewhere can be replayed here. The
+    /// conditions then persist for an hour before the forecast resumes.
+    pub fn set_metar(&mut self, text: &str, tick: u64, events: &mut Vec<SimEvent>) -> Result<Metar, MetarError> {
+        let metar = Metar::parse(text)?;
+        self.set_weather(metar.weather(), events);
+        self.weather_system.hold(self.weather, tick, 60);
+        Ok(metar)
+    }
+
     pub fn get_runway(&self, id: &str) -> Option<&Runway> {
         // --- FIX is here ---
         self.runways.iter().find(|r| r.id.eq_ignore_ascii_case(id))
     }
+
+    pub fn get_runway_mut(&mut self, id: &str) -> Option<&mut Runway> {
+        self.runways.iter_mut().find(|r| r.id.eq_ignore_ascii_case(id))
+    }
+
+    pub fn get_fix(&self, name: &str) -> Option<&Fix> {
+        self.fixes.iter().find(|f| f.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn get_gate(&self, id: &str) -> Option<&Gate> {
+        self.gates.iter().find(|g| g.id.eq_ignore_ascii_case(id))
+    }
+
+    pub fn get_gate_mut(&mut self, id: &str) -> Option<&mut Gate> {
+        self.gates.iter_mut().find(|g| g.id.eq_ignore_ascii_case(id))
+    }
+
+    /// Empty gates an aircraft of `size` fits at
+    pub fn free_gates(&self, size: GateSize) -> impl Iterator<Item = &Gate> {
+        self.gates.iter().filter(move |g| g.is_free() && g.fits(size))
+    }
+
+    /// Give `plane` gate `id`, freeing whichever gate it held before
+    pub fn assign_gate(&mut self, plane: &str, id: &str) {
+        self.release_gate(plane);
+        if let Some(gate) = self.get_gate_mut(id) {
+            gate.occupant = Some(plane.to_string());
+        }
+    }
+
+    /// Free the gate `plane` is parked at or assigned to
+    pub fn release_gate(&mut self, plane: &str) {
+        for gate in &mut self.gates {
+            if gate.occupant.as_deref() == Some(plane) {
+                gate.occupant = None;
+            }
+        }
+    }
+}
+
+impl Runway {
+    /// Head/tail and cross components of `wind` for this runway. Uses the
+    /// gust speed when there is one, since that is what limits apply to.
+    pub fn wind_components(&self, wind: &Wind) -> WindComponents {
+        let Some(direction) = wind.direction else {
+            // Variable wind could come from anywhere; assume straight across
+            return WindComponents {
+                headwind: 0.0,
+                crosswind: wind.peak_kt() as f64,
+            };
+        };
+        let angle = (direction as f64 - self.heading as f64).to_radians();
+        let speed = wind.peak_kt() as f64;
+        WindComponents {
+            headwind: speed * angle.cos(),
+            crosswind: speed * angle.sin(),
+        }
+    }
+
+    /// Lowest weather an approach to this runway may be flown in: CAT I
+    /// with an ILS, otherwise a visual approach
+    pub fn approach_minimums(&self) -> Minimums {
+        if self.ils {
+            Minimums {
+                ceiling_ft: 200,
+                visibility_sm: 0.5,
+            }
+        } else {
+            Minimums {
+                ceiling_ft: 1000,
+                visibility_sm: 3.0,
+            }
+        }
+    }
+
+    /// Who is on the runway at `tick`, if anyone. A plane on final has the
+    /// runway as much as one rolling out on it.
+    pub fn occupant(&self, tick: u64) -> Option<&str> {
+        self.occupancy
+            .as_ref()
+            .filter(|o| o.until.is_none_or(|until| tick < until))
+            .map(|o| o.plane.as_str())
+            .or(self.crossing.as_deref())
+            .or(self.on_final.as_deref())
+    }
+
+    /// How serious it is for a plane to stray onto the runway at `tick`,
+    /// going by who is using it
+    pub fn incursion_severity(&self, tick: u64) -> IncursionSeverity {
+        if self.on_final.is_some() {
+            return IncursionSeverity::A;
+        }
+        let Some(occupancy) = self
+            .occupancy
+            .as_ref()
+            .filter(|o| o.until.is_none_or(|until| tick < until))
+        else {
+            return IncursionSeverity::D;
+        };
+        if occupancy.until.is_none() {
+            return IncursionSeverity::C;
+        }
+        match self.last_movement.as_ref().map(|m| m.operation) {
+            Some(RunwayOperation::Landing) => IncursionSeverity::A,
+            _ => IncursionSeverity::B,
+        }
+    }
+
+    /// Note a takeoff or landing: the plane holds the runway for its
+    /// occupancy time, and leaves a wake behind it
+    pub fn record_movement(&mut self, movement: Movement) {
+        self.occupancy = Some(Occupancy {
+            plane: movement.plane.clone(),
+            until: Some(movement.tick + separation::occupancy_ticks(movement.operation)),
+        });
+        self.last_movement = Some(movement);
+    }
+
+    /// Keep whoever is on the runway there `ticks` longer, e.g. a plane
+    /// waiting to be towed off
+    pub fn extend_occupancy(&mut self, ticks: u64) {
+        if let Some(until) = self.occupancy.as_mut().and_then(|o| o.until.as_mut()) {
+            *until += ticks;
+        }
+    }
+
+    /// A vehicle holds the runway until `until`
+    pub fn hold_for_vehicle(&mut self, vehicle: &str, until: u64) {
+        self.occupancy = Some(Occupancy {
+            plane: vehicle.to_string(),
+            until: Some(until),
+        });
+    }
+
+    /// A departure lines up and holds the runway until cleared
+    pub fn line_up(&mut self, plane: &str) {
+        self.occupancy = Some(Occupancy {
+            plane: plane.to_string(),
+            until: None,
+        });
+    }
+
+    /// Whether `plane` is lined up and waiting here
+    pub fn is_lined_up(&self, plane: &str) -> bool {
+        self.occupancy
+            .as_ref()
+            .is_some_and(|o| o.until.is_none() && o.plane == plane)
+    }
+
+    /// Lowest visibility a departure may roll in. ILS runways have the
+    /// centreline lighting for low-visibility takeoffs.
+    pub fn takeoff_visibility_sm(&self) -> f64 {
+        if self.ils { 0.25 } else { 1.0 }
+    }
+}
+
+impl fmt::Display for GateSize {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            GateSize::Regional => "regional",
+            GateSize::Narrowbody => "narrowbody",
+            GateSize::Widebody => "widebody",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl fmt::Display for FlightCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            FlightCategory::Lifr => "LIFR",
+            FlightCategory::Ifr => "IFR",
+            FlightCategory::Mvfr => "MVFR",
+            FlightCategory::Vfr => "VFR",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl fmt::Display for Weather {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let wind = &self.wind;
+        if wind.speed_kt == 0 {
+            write!(f, "WIND CALM")?;
+        } else if let Some(direction) = wind.direction {
+            write!(f, "WIND {:03}/{}", direction, wind.speed_kt)?;
+            if let Some(gust) = wind.gust_kt {
+                write!(f, "G{}", gust)?;
+            }
+            write!(f, "KT")?;
+        } else {
+            write!(f, "WIND VRB/{}", wind.speed_kt)?;
+            if let Some(gust) = wind.gust_kt {
+                write!(f, "G{}", gust)?;
+            }
+            write!(f, "KT")?;
+        }
+        write!(f, " VIS {}SM", self.visibility_sm)?;
+        if self.rain {
+            write!(f, " RAIN")?;
+        }
+        match self.ceiling_ft {
+            Some(ceiling) => write!(f, " CIG {}FT", ceiling)?,
+            None => write!(f, " NO CIG")?,
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for AirportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AirportError::Io(e) => write!(f, "could not read airport file: {}", e),
+            AirportError::Parse(e) => write!(f, "invalid airport file: {}", e),
+            AirportError::Invalid(message) => write!(f, "invalid airport: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for AirportError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layouts_are_checked_against_the_navigation_database() {
+        let navdata = NavDatabase::new();
+        for source in [DEFAULT_AIRPORT, include_str!("../data/airports/kpdx_east.toml")] {
+            assert!(Airport::from_toml(source, &navdata).is_ok());
+        }
+        let unknown = DEFAULT_AIRPORT.replacen("icao = \"KPDX\"", "icao = \"ZZZZ\"", 1);
+        assert!(matches!(
+            Airport::from_toml(&unknown, &navdata),
+            Err(AirportError::Invalid(message)) if message.contains("ZZZZ")
+        ));
+    }
+
+    fn wind(direction: Option<u16>, speed_kt: u16) -> Wind {
+        Wind {
+            direction,
+            speed_kt,
+            gust_kt: None,
+        }
+    }
+
+    #[test]
+    fn resolves_a_wind_straight_down_a_north_south_runway() {
+        let airport = Airport::new();
+        let south = airport.get_runway("18").unwrap();
+        // The same strip flown the other way
+        let mut reversed = Airport::new();
+        let north = reversed.get_runway_mut("18").unwrap();
+        north.heading = 360;
+
+        let from_north = wind(Some(360), 20);
+        let components = north.wind_components(&from_north);
+        assert!((components.headwind - 20.0).abs() < 1e-9);
+        assert!(components.crosswind.abs() < 1e-9);
+        let components = south.wind_components(&from_north);
+        assert!((components.tailwind() - 20.0).abs() < 1e-9);
+        assert!(components.crosswind.abs() < 1e-9);
+
+        let from_south = wind(Some(180), 20);
+        assert!((south.wind_components(&from_south).headwind - 20.0).abs() < 1e-9);
+        assert!((north.wind_components(&from_south).tailwind() - 20.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn takes_a_variable_wind_as_all_crosswind() {
+        let airport = Airport::new();
+        let runway = airport.get_runway("18").unwrap();
+        let variable = Wind {
+            gust_kt: Some(15),
+            ..wind(None, 8)
+        };
+        let components = runway.wind_components(&variable);
+        assert_eq!(components.headwind, 0.0);
+        assert_eq!(components.crosswind, 15.0);
+        assert_eq!(runway.wind_components(&Wind::calm()).crosswind, 0.0);
+    }
 }
@@ -0,0 +1,384 @@
+use std::fmt;
+
+// --- COMMAND MODULE ---
+// Turns a line typed by the controller into a typed Command. This is the one
+// place that knows the command syntax; new verbs are added here and executed
+// in Game::execute.
+
+/// A syntactically valid controller instruction
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Pushback { plane: String },
+    /// Taxi to a runway or gate, optionally along named taxiways
+    Taxi {
+        plane: String,
+        destination: String,
+        via: Vec<String>,
+    },
+    /// Cross a runway on the taxi route
+    Cross { plane: String, runway: String },
+    /// Assign an arrival its gate
+    Gate { plane: String, gate: String },
+    /// Line up and wait on the runway
+    Lineup { plane: String, runway: String },
+    Takeoff { plane: String, runway: String },
+    Land { plane: String, runway: String },
+    /// Call out the emergency services an emergency needs
+    Alert { plane: String },
+    /// Abandon the approach and fly the missed approach
+    GoAround { plane: String },
+    /// Radar vectors: fly a heading
+    Heading { plane: String, heading: u16 },
+    /// Climb or descend to an altitude
+    Altitude { plane: String, altitude_ft: u32 },
+    Speed { plane: String, speed_kt: u16 },
+    /// Proceed direct to a named fix
+    Direct { plane: String, fix: String },
+    /// Hold at a fix's published holding pattern
+    Hold { plane: String, fix: String },
+    /// Move an arrival to land right after another
+    Sequence { plane: String, after: String },
+    Wait,
+    Quit,
+}
+
+/// Why a line could not be understood. These are typos, not operational
+/// mistakes, so they never cost a strike.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    Empty,
+    UnknownVerb(String),
+    MissingCallsign { verb: &'static str },
+    MissingRunway { verb: &'static str },
+    MalformedRunway(String),
+    MissingValue { verb: &'static str, what: &'static str },
+    MalformedValue { what: &'static str, value: String },
+}
+
+impl Command {
+    /// Parse a line such as `takeoff SWA123 24L`. Verbs are case-insensitive;
+    /// callsigns and runways are upper-cased.
+    pub fn parse(input: &str) -> Result<Command, ParseError> {
+        let mut parts = input.split_whitespace();
+        let verb = parts.next().ok_or(ParseError::Empty)?.to_lowercase();
+
+        match verb.as_str() {
+            "pushback" => Ok(Command::Pushback {
+                plane: callsign(parts.next(), "pushback")?,
+            }),
+            "taxi" => {
+                let plane = callsign(parts.next(), "taxi")?;
+                let destination = parts
+                    .next()
+                    .map(|s| s.to_uppercase())
+                    .ok_or(ParseError::MissingValue { verb: "taxi", what: "a runway or gate" })?;
+                Ok(Command::Taxi {
+                    plane,
+                    destination,
+                    via: via(parts)?,
+                })
+            }
+            "cross" => Ok(Command::Cross {
+                plane: callsign(parts.next(), "cross")?,
+                runway: runway(parts.next(), "cross")?,
+            }),
+            "gate" => Ok(Command::Gate {
+                plane: callsign(parts.next(), "gate")?,
+                gate: parts
+                    .next()
+                    .map(|s| s.to_uppercase())
+                    .ok_or(ParseError::MissingValue { verb: "gate", what: "a gate" })?,
+            }),
+            "lineup" => Ok(Command::Lineup {
+                plane: callsign(parts.next(), "lineup")?,
+                runway: runway(parts.next(), "lineup")?,
+            }),
+            "takeoff" => Ok(Command::Takeoff {
+                plane: callsign(parts.next(), "takeoff")?,
+                runway: runway(parts.next(), "takeoff")?,
+            }),
+            "land" => Ok(Command::Land {
+                plane: callsign(parts.next(), "land")?,
+                runway: runway(parts.next(), "land")?,
+            }),
+            "alert" => Ok(Command::Alert {
+                plane: callsign(parts.next(), "alert")?,
+            }),
+            "goaround" => Ok(Command::GoAround {
+                plane: callsign(parts.next(), "goaround")?,
+            }),
+            "heading" => Ok(Command::Heading {
+                plane: callsign(parts.next(), "heading")?,
+                heading: heading(parts.next())?,
+            }),
+            "climb" | "descend" => {
+                let verb = if verb == "climb" { "climb" } else { "descend" };
+                Ok(Command::Altitude {
+                    plane: callsign(parts.next(), verb)?,
+                    altitude_ft: altitude(parts.next(), verb)?,
+                })
+            }
+            "speed" => Ok(Command::Speed {
+                plane: callsign(parts.next(), "speed")?,
+                speed_kt: speed(parts.next())?,
+            }),
+            "direct" => Ok(Command::Direct {
+                plane: callsign(parts.next(), "direct")?,
+                fix: parts
+                    .next()
+                    .map(|s| s.to_uppercase())
+                    .ok_or(ParseError::MissingValue { verb: "direct", what: "a fix" })?,
+            }),
+            "hold" => Ok(Command::Hold {
+                plane: callsign(parts.next(), "hold")?,
+                fix: parts
+                    .next()
+                    .map(|s| s.to_uppercase())
+                    .ok_or(ParseError::MissingValue { verb: "hold", what: "a fix" })?,
+            }),
+            "sequence" => {
+                let plane = callsign(parts.next(), "sequence")?;
+                let after = match (parts.next(), parts.next()) {
+                    (Some(word), Some(leader)) if word.eq_ignore_ascii_case("after") => leader.to_uppercase(),
+                    _ => {
+                        return Err(ParseError::MissingValue {
+                            verb: "sequence",
+                            what: "'after' and the plane to follow",
+                        });
+                    }
+                };
+                Ok(Command::Sequence { plane, after })
+            }
+            "wait" => Ok(Command::Wait),
+            "quit" => Ok(Command::Quit),
+            _ => Err(ParseError::UnknownVerb(verb)),
+        }
+    }
+}
+
+fn callsign(part: Option<&str>, verb: &'static str) -> Result<String, ParseError> {
+    part.map(|s| s.to_uppercase())
+        .ok_or(ParseError::MissingCallsign { verb })
+}
+
+/// A runway designator is a heading 01-36 with an optional L/C/R suffix
+fn runway(part: Option<&str>, verb: &'static str) -> Result<String, ParseError> {
+    let id = part
+        .map(|s| s.to_uppercase())
+        .ok_or(ParseError::MissingRunway { verb })?;
+
+    let digits = id.trim_end_matches(['L', 'C', 'R']);
+    let suffix_len = id.len() - digits.len();
+    let heading_ok = matches!(digits.parse::<u32>(), Ok(1..=36))
+        && (1..=2).contains(&digits.len())
+        && digits.chars().all(|c| c.is_ascii_digit());
+
+    if heading_ok && suffix_len <= 1 {
+        Ok(id)
+    } else {
+        Err(ParseError::MalformedRunway(id))
+    }
+}
+
+/// An optional `via A B ...` list of taxiways
+fn via<'a>(mut parts: impl Iterator<Item = &'a str>) -> Result<Vec<String>, ParseError> {
+    match parts.next() {
+        None => Ok(Vec::new()),
+        Some(word) if word.eq_ignore_ascii_case("via") => {
+            let taxiways: Vec<String> = parts.map(|s| s.to_uppercase()).collect();
+            if taxiways.is_empty() {
+                Err(ParseError::MissingValue { verb: "taxi", what: "taxiways after 'via'" })
+            } else {
+                Ok(taxiways)
+            }
+        }
+        Some(word) => Err(ParseError::MalformedValue {
+            what: "taxi route (expected 'via' and taxiway names)",
+            value: word.to_string(),
+        }),
+    }
+}
+
+/// A heading is 1-360 degrees
+fn heading(part: Option<&str>) -> Result<u16, ParseError> {
+    let value = part.ok_or(ParseError::MissingValue { verb: "heading", what: "a heading" })?;
+    match value.parse::<u16>() {
+        Ok(heading @ 1..=360) => Ok(heading),
+        _ => Err(ParseError::MalformedValue {
+            what: "heading (1-360)",
+            value: value.to_string(),
+        }),
+    }
+}
+
+/// An altitude in feet, or a flight level such as FL120
+fn altitude(part: Option<&str>, verb: &'static str) -> Result<u32, ParseError> {
+    let value = part.ok_or(ParseError::MissingValue { verb, what: "an altitude" })?;
+    let upper = value.to_uppercase();
+    let parsed = match upper.strip_prefix("FL") {
+        Some(level) => level.parse::<u32>().map(|fl| fl * 100),
+        None => upper.parse::<u32>(),
+    };
+    parsed.map_err(|_| ParseError::MalformedValue {
+        what: "altitude (e.g. 5000 or FL120)",
+        value: value.to_string(),
+    })
+}
+
+fn speed(part: Option<&str>) -> Result<u16, ParseError> {
+    let value = part.ok_or(ParseError::MissingValue { verb: "speed", what: "a speed" })?;
+    value.parse::<u16>().map_err(|_| ParseError::MalformedValue {
+        what: "speed in knots",
+        value: value.to_string(),
+    })
+}
+
+/// A sample argument for `verb`, used in error messages
+fn example(verb: &str) -> &'static str {
+    match verb {
+        "heading" => "270",
+        "climb" | "descend" => "5000",
+        "speed" => "210",
+        "direct" | "hold" => "BRAVO",
+        "sequence" => "after UAL77",
+        "gate" => "C1",
+        "taxi" => "24L via A B",
+        _ => "",
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "No command entered."),
+            ParseError::UnknownVerb(verb) => write!(f, "Unknown command: {}", verb),
+            ParseError::MissingCallsign { verb } => {
+                write!(f, "'{}' needs a callsign, e.g. {} SWA123", verb, verb)
+            }
+            ParseError::MissingRunway { verb } => {
+                write!(f, "'{}' needs a runway, e.g. {} SWA123 24L", verb, verb)
+            }
+            ParseError::MalformedRunway(id) => {
+                write!(f, "'{}' is not a runway designator (e.g. 18, 24L).", id)
+            }
+            ParseError::MissingValue { verb, what } => write!(
+                f,
+                "'{}' needs {}, e.g. {} DAL456 {}",
+                verb,
+                what,
+                verb,
+                example(verb)
+            ),
+            ParseError::MalformedValue { what, value } => {
+                write!(f, "'{}' is not a valid {}.", value, what)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_verbs_case_insensitively() {
+        assert_eq!(
+            Command::parse("TakeOff swa123 24l"),
+            Ok(Command::Takeoff {
+                plane: "SWA123".to_string(),
+                runway: "24L".to_string(),
+            })
+        );
+        assert_eq!(Command::parse("  wait  "), Ok(Command::Wait));
+    }
+
+    #[test]
+    fn parses_taxi_routes() {
+        assert_eq!(
+            Command::parse("taxi swa123 24l via a b"),
+            Ok(Command::Taxi {
+                plane: "SWA123".to_string(),
+                destination: "24L".to_string(),
+                via: vec!["A".to_string(), "B".to_string()],
+            })
+        );
+        assert_eq!(
+            Command::parse("taxi dal456 c1"),
+            Ok(Command::Taxi {
+                plane: "DAL456".to_string(),
+                destination: "C1".to_string(),
+                via: Vec::new(),
+            })
+        );
+        assert!(matches!(
+            Command::parse("taxi swa123 24l via"),
+            Err(ParseError::MissingValue { verb: "taxi", .. })
+        ));
+        assert!(matches!(
+            Command::parse("taxi swa123 24l along a"),
+            Err(ParseError::MalformedValue { .. })
+        ));
+    }
+
+    #[test]
+    fn parses_altitudes_and_flight_levels() {
+        assert_eq!(
+            Command::parse("climb ual1 FL120"),
+            Ok(Command::Altitude {
+                plane: "UAL1".to_string(),
+                altitude_ft: 12000,
+            })
+        );
+        assert_eq!(
+            Command::parse("descend ual1 3000"),
+            Ok(Command::Altitude {
+                plane: "UAL1".to_string(),
+                altitude_ft: 3000,
+            })
+        );
+    }
+
+    #[test]
+    fn parses_sequence_after() {
+        assert_eq!(
+            Command::parse("sequence ual1 after dal2"),
+            Ok(Command::Sequence {
+                plane: "UAL1".to_string(),
+                after: "DAL2".to_string(),
+            })
+        );
+        assert!(Command::parse("sequence ual1 dal2").is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        assert_eq!(Command::parse(""), Err(ParseError::Empty));
+        assert_eq!(Command::parse("fly ual1"), Err(ParseError::UnknownVerb("fly".to_string())));
+        assert_eq!(
+            Command::parse("pushback"),
+            Err(ParseError::MissingCallsign { verb: "pushback" })
+        );
+        assert_eq!(
+            Command::parse("land ual1"),
+            Err(ParseError::MissingRunway { verb: "land" })
+        );
+        assert_eq!(
+            Command::parse("land ual1 37"),
+            Err(ParseError::MalformedRunway("37".to_string()))
+        );
+        assert_eq!(
+            Command::parse("land ual1 24LR"),
+            Err(ParseError::MalformedRunway("24LR".to_string()))
+        );
+        assert!(matches!(
+            Command::parse("heading ual1 0"),
+            Err(ParseError::MalformedValue { .. })
+        ));
+        assert!(matches!(
+            Command::parse("heading ual1 361"),
+            Err(ParseError::MalformedValue { .. })
+        ));
+    }
+}
@@ -0,0 +1,191 @@
+use crate::plane::PlaneStatus;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+// --- EMERGENCY MODULE ---
+// Abnormal situations a crew can declare: what each one does to the plane,
+// which runways it can use and which services must meet it. Emergencies are
+// declared at random by Game::update, or by a scenario with `@emergency`.
+
+/// Chance each tick that one airborne plane declares an emergency
+pub const CHANCE_PER_TICK: f64 = 0.01;
+/// Chance that a new arrival is a diversion from another field
+pub const DIVERSION_CHANCE: f64 = 0.05;
+/// Bonus for landing an emergency the tick it was declared; one point less
+/// for every tick after that
+pub const PROMPT_LANDING_BONUS: i32 = 25;
+/// Ticks a vehicle spends sweeping a runway for debris after a bird strike
+pub const SWEEP_TICKS: u64 = 5;
+/// Name the sweeping vehicle is shown under while it holds the runway
+pub const SWEEP_VEHICLE: &str = "SWEEPER";
+/// Ticks a plane with failed hydraulics blocks the runway until towed off
+pub const TOW_TICKS: u64 = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmergencyKind {
+    /// Lost an engine: slower to climb and accelerate
+    EngineFailure,
+    /// A passenger needs a hospital
+    Medical,
+    /// Hit birds on the climb out; returns and the runway is swept
+    BirdStrike,
+    /// No flaps or brakes worth the name: needs a long runway, then stops on it
+    HydraulicFailure,
+    /// Coming to us instead of its destination
+    Diversion,
+}
+
+/// Who must be waiting when an emergency lands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Services {
+    FireRescue,
+    Paramedics,
+}
+
+/// An emergency in progress on one plane
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Emergency {
+    pub kind: EmergencyKind,
+    /// Tick it was declared, for the prompt-landing bonus
+    pub declared_tick: u64,
+    /// Whether the controller has called out the services it needs
+    pub services_alerted: bool,
+}
+
+/// Why an emergency could not be declared
+#[derive(Debug, Clone, PartialEq)]
+pub enum EmergencyError {
+    UnknownKind(String),
+    UnknownPlane(String),
+    AlreadyDeclared(String),
+    /// The plane is not in a phase of flight where this can happen
+    WrongPhase { plane: String, kind: EmergencyKind },
+}
+
+impl EmergencyKind {
+    pub const ALL: [EmergencyKind; 5] = [
+        EmergencyKind::EngineFailure,
+        EmergencyKind::Medical,
+        EmergencyKind::BirdStrike,
+        EmergencyKind::HydraulicFailure,
+        EmergencyKind::Diversion,
+    ];
+
+    /// Whether a plane in `status` can have this emergency. Bird strikes
+    /// happen on the climb out, diversions are arrivals; the rest can
+    /// happen to anyone in the air.
+    pub fn can_happen(self, status: PlaneStatus) -> bool {
+        let arriving = matches!(
+            status,
+            PlaneStatus::InAir | PlaneStatus::ReadyToLand | PlaneStatus::Holding | PlaneStatus::MissedApproach
+        );
+        match self {
+            EmergencyKind::BirdStrike => status == PlaneStatus::Departing,
+            EmergencyKind::Diversion => arriving,
+            _ => arriving || status == PlaneStatus::Departing,
+        }
+    }
+
+    /// MAYDAY for a threat to the aircraft, PAN PAN otherwise
+    pub fn is_distress(self) -> bool {
+        !matches!(self, EmergencyKind::Medical | EmergencyKind::Diversion)
+    }
+
+    pub fn services(self) -> Option<Services> {
+        match self {
+            EmergencyKind::EngineFailure | EmergencyKind::BirdStrike | EmergencyKind::HydraulicFailure => {
+                Some(Services::FireRescue)
+            }
+            EmergencyKind::Medical => Some(Services::Paramedics),
+            EmergencyKind::Diversion => None,
+        }
+    }
+
+    /// Runway the plane needs, as a multiple of its type's normal length
+    pub fn runway_factor(self) -> f64 {
+        match self {
+            EmergencyKind::HydraulicFailure => 1.2,
+            _ => 1.0,
+        }
+    }
+}
+
+impl Emergency {
+    pub fn new(kind: EmergencyKind, tick: u64) -> Self {
+        Emergency {
+            kind,
+            declared_tick: tick,
+            services_alerted: false,
+        }
+    }
+
+    /// Whether the services it needs are waiting for it
+    pub fn services_ready(&self) -> bool {
+        self.services_alerted || self.kind.services().is_none()
+    }
+
+    /// Points for landing it at `tick`
+    pub fn landing_bonus(&self, tick: u64) -> i32 {
+        let waited = i32::try_from(tick.saturating_sub(self.declared_tick)).unwrap_or(i32::MAX);
+        (PROMPT_LANDING_BONUS - waited).max(0)
+    }
+}
+
+impl FromStr for EmergencyKind {
+    type Err = EmergencyError;
+
+    /// The names used in scripts, e.g. `engine_failure`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "engine_failure" => Ok(EmergencyKind::EngineFailure),
+            "medical" => Ok(EmergencyKind::Medical),
+            "bird_strike" => Ok(EmergencyKind::BirdStrike),
+            "hydraulic_failure" => Ok(EmergencyKind::HydraulicFailure),
+            "diversion" => Ok(EmergencyKind::Diversion),
+            _ => Err(EmergencyError::UnknownKind(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for EmergencyKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            EmergencyKind::EngineFailure => "engine failure",
+            EmergencyKind::Medical => "medical emergency",
+            EmergencyKind::BirdStrike => "bird strike",
+            EmergencyKind::HydraulicFailure => "hydraulic failure",
+            EmergencyKind::Diversion => "diversion",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl fmt::Display for Services {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Services::FireRescue => write!(f, "fire and rescue"),
+            Services::Paramedics => write!(f, "paramedics"),
+        }
+    }
+}
+
+impl fmt::Display for EmergencyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EmergencyError::UnknownKind(kind) => write!(
+                f,
+                "unknown emergency '{}' (engine_failure, medical, bird_strike, hydraulic_failure, diversion)",
+                kind
+            ),
+            EmergencyError::UnknownPlane(plane) => write!(f, "no plane {}", plane),
+            EmergencyError::AlreadyDeclared(plane) => write!(f, "{} already has an emergency", plane),
+            EmergencyError::WrongPhase { plane, kind } => {
+                write!(f, "{} can't have a {} now", plane, kind)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EmergencyError {}
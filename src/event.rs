@@ -0,0 +1,721 @@
+use crate::aircraft::WeightClass;
+use crate::airport::{ClosureReason, FlightCategory, GateSize, RunwayOperation, Weather};
+use crate::command::ParseError;
+use crate::emergency::{EmergencyKind, Services};
+use crate::fuel::FuelState;
+use crate::ground::TaxiTarget;
+use crate::plane::PlaneStatus;
+use crate::separation::{IncursionSeverity, Proximity};
+use std::fmt;
+
+// --- EVENT MODULE ---
+// Everything the engine has to say is reported as a SimEvent. The engine never
+// prints; frontends drain the events after each command/tick and render them.
+
+/// A clearance the controller issued successfully
+#[derive(Debug, Clone, PartialEq)]
+pub enum Clearance {
+    Pushback,
+    Taxi { target: TaxiTarget, via: Vec<String> },
+    LineUp { runway: String },
+    Cross { runway: String },
+    Gate { gate: String },
+    Takeoff { runway: String },
+    Land { runway: String },
+    Heading { heading: u16, right: bool },
+    Altitude { altitude_ft: u32, climbing: bool },
+    Speed { speed_kt: u16 },
+    Direct { fix: String },
+    /// Emergency services called out to meet the plane
+    Alert { services: Services },
+    /// Enter the published hold at a fix
+    Hold { fix: String, altitude_ft: u32 },
+    /// Take a place in the landing sequence
+    Sequence { after: String, number: usize },
+}
+
+/// Why a penalty strike was given
+#[derive(Debug, Clone, PartialEq)]
+pub enum PenaltyReason {
+    UnknownPlane(String),
+    StillBoarding(String),
+    NotReadyForPushback(String),
+    NotReadyForTakeoff(String),
+    NotReadyToLand(String),
+    RunwayClosed {
+        plane: String,
+        runway: String,
+        operation: RunwayOperation,
+    },
+    BelowMinimums {
+        plane: String,
+        runway: String,
+        category: FlightCategory,
+    },
+    CrosswindExceeded {
+        plane: String,
+        runway: String,
+        crosswind_kt: u16,
+        limit_kt: u16,
+    },
+    TailwindExceeded {
+        plane: String,
+        runway: String,
+        tailwind_kt: u16,
+        limit_kt: u16,
+    },
+    UnknownRunway(String),
+    /// Shorter than the aircraft type needs
+    RunwayTooShort {
+        plane: String,
+        runway: String,
+        length_ft: u32,
+        required_ft: u32,
+    },
+    NotAirborne(String),
+    AltitudeOutOfRange {
+        plane: String,
+        altitude_ft: u32,
+        floor_ft: u32,
+        ceiling_ft: u32,
+    },
+    SpeedOutOfRange {
+        plane: String,
+        speed_kt: u16,
+        min_kt: u16,
+        max_kt: u16,
+    },
+    UnknownFix(String),
+    /// Only a plane cleared to land can be sent around
+    NotOnFinal(String),
+    /// A plane on final flies the approach; it must go around to be vectored
+    OnFinal(String),
+    /// Sent around from a runway it could have landed on
+    UnnecessaryGoAround {
+        plane: String,
+        runway: String,
+    },
+    RunwayOccupied {
+        plane: String,
+        runway: String,
+        occupant: String,
+    },
+    /// Cleared onto a runway other than the one it is holding short of
+    WrongRunway {
+        plane: String,
+        at: String,
+        runway: String,
+    },
+    NotReadyToTaxi(String),
+    UnknownGate(String),
+    /// Departures taxi to a runway, arrivals to a gate
+    WrongTaxiDestination {
+        plane: String,
+        target: TaxiTarget,
+    },
+    NoTaxiRoute {
+        plane: String,
+        target: TaxiTarget,
+        via: Vec<String>,
+    },
+    WakeTurbulence {
+        plane: String,
+        runway: String,
+        leader: String,
+        leader_class: WeightClass,
+        wait_ticks: u64,
+    },
+    /// Cleared across a runway its taxi route doesn't cross
+    NoCrossing {
+        plane: String,
+        runway: String,
+    },
+    /// Gates are assigned to arrivals only
+    NotArriving(String),
+    GateOccupied {
+        plane: String,
+        gate: String,
+        occupant: String,
+    },
+    GateTooSmall {
+        plane: String,
+        gate: String,
+        size: GateSize,
+        needed: GateSize,
+    },
+    /// Services alerted for a plane with no emergency that needs them
+    NoEmergency(String),
+    /// An emergency landed with nobody waiting for it
+    ServicesNotAlerted {
+        plane: String,
+        services: Services,
+    },
+    /// Only arrivals not yet cleared to land can hold
+    CannotHold(String),
+    NoPublishedHold(String),
+    /// Every level of the stack is taken
+    HoldFull(String),
+    /// Named in a sequence command but not an airborne arrival
+    NotInSequence(String),
+    /// Cleared to land while a plane ahead of it in the sequence is ready
+    OutOfSequence {
+        plane: String,
+        ahead: String,
+    },
+    /// Cleared to land ahead of an emergency that was waiting
+    NotPrioritised {
+        plane: String,
+        priority: String,
+    },
+}
+
+/// Who put the plane on the runway
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IncursionCause {
+    /// The pilot crossed without a clearance
+    PilotDeviation,
+    /// The controller cleared a crossing onto a runway in use
+    CrossingClearance,
+}
+
+/// A plane on a runway it shouldn't have been on
+#[derive(Debug, Clone, PartialEq)]
+pub struct Incursion {
+    pub plane: String,
+    pub runway: String,
+    pub severity: IncursionSeverity,
+    pub cause: IncursionCause,
+}
+
+/// A safety event between aircraft. Incidents are counted separately from
+/// penalty strikes and are never forgiven by a good clearance.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Incident {
+    LossOfSeparation {
+        planes: (String, String),
+        proximity: Proximity,
+    },
+    Collision {
+        planes: (String, String),
+        proximity: Proximity,
+    },
+    /// Two planes taxiing at each other on the same segment
+    HeadOnTaxi {
+        planes: (String, String),
+        taxiway: String,
+    },
+    RunwayIncursion(Incursion),
+    /// A plane ran out of fuel in the air
+    FuelExhaustion { plane: String },
+}
+
+/// Why a plane abandoned its approach
+#[derive(Debug, Clone, PartialEq)]
+pub enum GoAroundReason {
+    /// The controller told it to
+    Instructed,
+    RunwayOccupied { occupant: String },
+    RunwayClosed,
+}
+
+/// Why a session ended early
+#[derive(Debug, Clone, PartialEq)]
+pub enum GameOverReason {
+    /// Three penalty strikes in a row
+    Fired,
+    MidAirCollision { planes: (String, String) },
+    /// A plane ran its tanks dry before it could land
+    FuelExhaustion { plane: String },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SimEvent {
+    /// A new plane entered the simulation
+    PlaneSpawned {
+        plane: String,
+        origin: String,
+        destination: String,
+        arriving: bool,
+    },
+    /// A plane advanced to its next state on its own during a tick
+    PlaneStateChanged {
+        plane: String,
+        from: PlaneStatus,
+        to: PlaneStatus,
+    },
+    WeatherChanged(Weather),
+    RunwayClosed { runway: String, reason: ClosureReason },
+    RunwayReopened { runway: String },
+    Clearance { plane: String, clearance: Clearance },
+    Penalty { reason: PenaltyReason, strikes: u32 },
+    ScoreChanged { delta: i32, score: i32 },
+    /// A plane on final abandoned its approach
+    GoAround {
+        plane: String,
+        runway: String,
+        reason: GoAroundReason,
+    },
+    /// A crew declared an emergency; `returning` if it was departing and
+    /// is coming back
+    Emergency {
+        plane: String,
+        kind: EmergencyKind,
+        returning: bool,
+    },
+    /// A crew declared minimum or MAYDAY fuel, with `minutes` left
+    FuelDeclared {
+        plane: String,
+        state: FuelState,
+        minutes: u32,
+    },
+    /// A taxiing plane has stopped at a runway and asks to cross it
+    HoldingShort { plane: String, runway: String },
+    /// The controller chose to let a tick pass
+    Holding,
+    /// A line that could not be parsed; no penalty is given
+    CommandRejected(ParseError),
+    Info(String),
+    /// A loss of separation or worse; `total` counts the session's incidents
+    Incident { incident: Incident, total: u32 },
+    /// The session is over: fired, or an accident
+    GameOver { final_score: i32, reason: GameOverReason },
+    /// The controller quit the session
+    SessionEnded,
+}
+
+fn clearance_phrase(operation: RunwayOperation) -> &'static str {
+    match operation {
+        RunwayOperation::Takeoff => "for takeoff",
+        RunwayOperation::Landing => "to land",
+    }
+}
+
+impl fmt::Display for PenaltyReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PenaltyReason::UnknownPlane(id) => write!(f, "Plane not found: {}", id),
+            PenaltyReason::StillBoarding(id) => {
+                write!(f, "{} is still boarding! Can't pushback!", id)
+            }
+            PenaltyReason::NotReadyForPushback(id) => write!(f, "{} is not ready for pushback.", id),
+            PenaltyReason::NotReadyForTakeoff(id) => write!(f, "{} is not ready for takeoff.", id),
+            PenaltyReason::NotReadyToLand(id) => write!(f, "{} is not ready to land.", id),
+            PenaltyReason::RunwayClosed {
+                plane,
+                runway,
+                operation,
+            } => write!(
+                f,
+                "Runway {} is CLOSED! You can't clear {} {}!",
+                runway,
+                plane,
+                clearance_phrase(*operation)
+            ),
+            PenaltyReason::BelowMinimums {
+                plane,
+                runway,
+                category,
+            } => write!(
+                f,
+                "Weather ({}) is below runway {}'s approach minimums! You can't clear {} to land!",
+                category, runway, plane
+            ),
+            PenaltyReason::CrosswindExceeded {
+                plane,
+                runway,
+                crosswind_kt,
+                limit_kt,
+            } => write!(
+                f,
+                "{} kt crosswind on runway {} is over {}'s {} kt limit!",
+                crosswind_kt, runway, plane, limit_kt
+            ),
+            PenaltyReason::TailwindExceeded {
+                plane,
+                runway,
+                tailwind_kt,
+                limit_kt,
+            } => write!(
+                f,
+                "{} kt tailwind on runway {} is over {}'s {} kt limit!",
+                tailwind_kt, runway, plane, limit_kt
+            ),
+            PenaltyReason::UnknownRunway(id) => write!(f, "Runway {} does not exist!", id),
+            PenaltyReason::RunwayTooShort {
+                plane,
+                runway,
+                length_ft,
+                required_ft,
+            } => write!(
+                f,
+                "Runway {} is {} ft long, {} needs {} ft!",
+                runway, length_ft, plane, required_ft
+            ),
+            PenaltyReason::NotAirborne(id) => write!(f, "{} is not in the air!", id),
+            PenaltyReason::AltitudeOutOfRange {
+                plane,
+                altitude_ft,
+                floor_ft,
+                ceiling_ft,
+            } => write!(
+                f,
+                "{} can't be assigned {} ft (allowed {}-{} ft)!",
+                plane, altitude_ft, floor_ft, ceiling_ft
+            ),
+            PenaltyReason::SpeedOutOfRange {
+                plane,
+                speed_kt,
+                min_kt,
+                max_kt,
+            } => write!(
+                f,
+                "{} can't fly {} kt (allowed {}-{} kt)!",
+                plane, speed_kt, min_kt, max_kt
+            ),
+            PenaltyReason::UnknownFix(name) => write!(f, "Fix {} does not exist!", name),
+            PenaltyReason::NotOnFinal(id) => write!(f, "{} is not on final, there is nothing to go around from.", id),
+            PenaltyReason::OnFinal(id) => write!(f, "{} is on final! Send it around before giving it vectors.", id),
+            PenaltyReason::UnnecessaryGoAround { plane, runway } => write!(
+                f,
+                "Runway {} was clear! {} didn't need to go around.",
+                runway, plane
+            ),
+            PenaltyReason::RunwayOccupied {
+                plane,
+                runway,
+                occupant,
+            } => write!(
+                f,
+                "Runway {} is occupied by {}! You can't clear {} onto it!",
+                runway, occupant, plane
+            ),
+            PenaltyReason::WrongRunway { plane, at, runway } => {
+                write!(f, "{} is at runway {}, not {}!", plane, at, runway)
+            }
+            PenaltyReason::NotReadyToTaxi(id) => write!(f, "{} is not ready to taxi.", id),
+            PenaltyReason::UnknownGate(id) => write!(f, "Gate {} does not exist!", id),
+            PenaltyReason::WrongTaxiDestination { plane, target } => write!(
+                f,
+                "{} can't taxi to {}: departures taxi to a runway, arrivals to a gate.",
+                plane, target
+            ),
+            PenaltyReason::NoTaxiRoute { plane, target, via } if via.is_empty() => {
+                write!(f, "There is no route for {} to {}!", plane, target)
+            }
+            PenaltyReason::NoTaxiRoute { plane, target, via } => write!(
+                f,
+                "There is no route for {} to {} via {}!",
+                plane,
+                target,
+                via.join(" ")
+            ),
+            PenaltyReason::WakeTurbulence {
+                plane,
+                runway,
+                leader,
+                leader_class,
+                wait_ticks,
+            } => write!(
+                f,
+                "{} is too close behind {} ({}) on runway {}! Wait {} more tick(s) for the wake to clear.",
+                plane, leader, leader_class, runway, wait_ticks
+            ),
+            PenaltyReason::NoCrossing { plane, runway } => {
+                write!(f, "{}'s taxi route doesn't cross runway {}!", plane, runway)
+            }
+            PenaltyReason::NotArriving(id) => {
+                write!(f, "{} is not an arrival, it can't be given a gate.", id)
+            }
+            PenaltyReason::GateOccupied {
+                plane,
+                gate,
+                occupant,
+            } => write!(f, "Gate {} is taken by {}! You can't give it to {}!", gate, occupant, plane),
+            PenaltyReason::GateTooSmall {
+                plane,
+                gate,
+                size,
+                needed,
+            } => write!(
+                f,
+                "Gate {} is a {} gate, {} needs a {} gate!",
+                gate, size, plane, needed
+            ),
+            PenaltyReason::NoEmergency(id) => {
+                write!(f, "{} has no emergency that needs services.", id)
+            }
+            PenaltyReason::ServicesNotAlerted { plane, services } => write!(
+                f,
+                "{} landed with nobody waiting, {} were never alerted!",
+                plane, services
+            ),
+            PenaltyReason::CannotHold(id) => {
+                write!(f, "{} is not an arrival waiting to land, it can't hold.", id)
+            }
+            PenaltyReason::NoPublishedHold(fix) => write!(f, "There is no published hold at {}.", fix),
+            PenaltyReason::HoldFull(fix) => write!(f, "The hold at {} is full, every level is taken!", fix),
+            PenaltyReason::NotInSequence(id) => {
+                write!(f, "{} is not in the arrival sequence.", id)
+            }
+            PenaltyReason::OutOfSequence { plane, ahead } => write!(
+                f,
+                "{} is ahead of {} in the landing sequence and ready to land!",
+                ahead, plane
+            ),
+            PenaltyReason::NotPrioritised { plane, priority } => write!(
+                f,
+                "{} is an emergency and lands before {}!",
+                priority, plane
+            ),
+        }
+    }
+}
+
+impl fmt::Display for Incident {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Incident::LossOfSeparation { planes, proximity } => write!(
+                f,
+                "LOSS OF SEPARATION: {} and {} came within {:.1} NM / {:.0} ft",
+                planes.0, planes.1, proximity.horizontal_nm, proximity.vertical_ft
+            ),
+            Incident::Collision { planes, .. } => {
+                write!(f, "MID-AIR COLLISION: {} and {}", planes.0, planes.1)
+            }
+            Incident::HeadOnTaxi { planes, taxiway } => write!(
+                f,
+                "HEAD-ON ON TAXIWAY {}: {} and {} are taxiing at each other",
+                taxiway, planes.0, planes.1
+            ),
+            Incident::RunwayIncursion(incursion) => {
+                let Incursion {
+                    plane,
+                    runway,
+                    severity,
+                    cause,
+                } = incursion;
+                write!(f, "RUNWAY INCURSION (CATEGORY {}): ", severity)?;
+                match cause {
+                    IncursionCause::PilotDeviation => {
+                        write!(f, "{} crossed runway {} without a clearance", plane, runway)
+                    }
+                    IncursionCause::CrossingClearance => {
+                        write!(f, "{} was cleared across runway {} while it was in use", plane, runway)
+                    }
+                }
+            }
+            Incident::FuelExhaustion { plane } => {
+                write!(f, "FUEL EXHAUSTION: {} has run its tanks dry", plane)
+            }
+        }
+    }
+}
+
+impl fmt::Display for GameOverReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameOverReason::Fired => write!(f, "Alright, get out, you're fired."),
+            GameOverReason::MidAirCollision { planes } => write!(
+                f,
+                "{} and {} have collided. The airport is closed and so is your career.",
+                planes.0, planes.1
+            ),
+            GameOverReason::FuelExhaustion { plane } => write!(
+                f,
+                "{} ran out of fuel holding in your airspace. The investigators would like a word.",
+                plane
+            ),
+        }
+    }
+}
+
+impl fmt::Display for SimEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SimEvent::PlaneSpawned {
+                plane,
+                origin,
+                destination,
+                arriving: true,
+            } => write!(
+                f,
+                "NEW PLANE: {} is approaching ({} -> {}), will be ready to land soon.",
+                plane, origin, destination
+            ),
+            SimEvent::PlaneSpawned {
+                plane,
+                origin,
+                destination,
+                arriving: false,
+            } => write!(
+                f,
+                "NEW PLANE: {} is at the gate ({} -> {}), beginning boarding.",
+                plane, origin, destination
+            ),
+            SimEvent::PlaneStateChanged {
+                plane,
+                from: PlaneStatus::Holding,
+                to: PlaneStatus::InAir,
+            } => write!(f, "INFO: {} is leaving the hold, inbound.", plane),
+            SimEvent::PlaneStateChanged { plane, to, .. } => match to {
+                PlaneStatus::ReadyForPushback => {
+                    write!(f, "INFO: {} is fully boarded and ready for pushback.", plane)
+                }
+                PlaneStatus::ReadyForTakeoff => {
+                    write!(f, "INFO: {} is at the runway, ready for takeoff.", plane)
+                }
+                PlaneStatus::ReadyToLand => {
+                    write!(f, "INFO: {} (in air) is requesting to land.", plane)
+                }
+                PlaneStatus::TaxiingToGate => write!(f, "INFO: {} has landed, vacating the runway.", plane),
+                PlaneStatus::InAir => write!(
+                    f,
+                    "INFO: {} has flown the missed approach, rejoining the arrivals.",
+                    plane
+                ),
+                PlaneStatus::HoldingForGate => {
+                    write!(f, "INFO: {} is holding on the ramp, waiting for a gate.", plane)
+                }
+                PlaneStatus::AtGate => write!(f, "INFO: {} has arrived at the gate.", plane),
+                PlaneStatus::HandedOff => {
+                    write!(f, "INFO: {} has left the airspace, handed off.", plane)
+                }
+                other => write!(f, "INFO: {} is now {:?}.", plane, other),
+            },
+            SimEvent::WeatherChanged(weather) => {
+                write!(f, "WEATHER UPDATE: {}", weather)
+            }
+            SimEvent::RunwayClosed { runway, reason } => match reason {
+                ClosureReason::LowVisibility => write!(
+                    f,
+                    "RUNWAY INFO: Runway {} closed, visibility below takeoff minimums.",
+                    runway
+                ),
+            },
+            SimEvent::RunwayReopened { runway } => {
+                write!(f, "RUNWAY INFO: Runway {} is open again.", runway)
+            }
+            SimEvent::Clearance { plane, clearance } => match clearance {
+                Clearance::Pushback => write!(f, "ATC: {} cleared for pushback.", plane),
+                Clearance::Taxi { target, via } if via.is_empty() => {
+                    write!(f, "ATC: {} taxi to {}.", plane, target)
+                }
+                Clearance::Taxi { target, via } => {
+                    write!(f, "ATC: {} taxi to {} via {}.", plane, target, via.join(", "))
+                }
+                Clearance::LineUp { runway } => {
+                    write!(f, "ATC: {} runway {}, line up and wait.", plane, runway)
+                }
+                Clearance::Cross { runway } => {
+                    write!(f, "ATC: {} cross runway {}.", plane, runway)
+                }
+                Clearance::Gate { gate } => write!(f, "ATC: {} your gate is {}.", plane, gate),
+                Clearance::Hold { fix, altitude_ft } => write!(
+                    f,
+                    "ATC: {} hold at {} as published, maintain {} ft.",
+                    plane, fix, altitude_ft
+                ),
+                Clearance::Sequence { after, number } => {
+                    write!(f, "ATC: {} number {}, follow {}.", plane, number, after)
+                }
+                Clearance::Alert { services } => {
+                    write!(f, "ATC: {} {} alerted, standing by for your arrival.", plane, services)
+                }
+                Clearance::Takeoff { runway } => {
+                    write!(f, "ATC: {} cleared for takeoff, runway {}.", plane, runway)
+                }
+                Clearance::Land { runway } => {
+                    write!(f, "ATC: {} cleared to land, runway {}.", plane, runway)
+                }
+                Clearance::Heading { heading, right } => {
+                    let side = if *right { "right" } else { "left" };
+                    write!(f, "ATC: {} turn {} heading {:03}.", plane, side, heading)
+                }
+                Clearance::Altitude {
+                    altitude_ft,
+                    climbing,
+                } => {
+                    let verb = if *climbing { "climb" } else { "descend" };
+                    write!(f, "ATC: {} {} and maintain {} ft.", plane, verb, altitude_ft)
+                }
+                Clearance::Speed { speed_kt } => {
+                    write!(f, "ATC: {} maintain {} knots.", plane, speed_kt)
+                }
+                Clearance::Direct { fix } => {
+                    write!(f, "ATC: {} proceed direct {}.", plane, fix)
+                }
+            },
+            SimEvent::Penalty { reason, strikes } => {
+                write!(f, "PENALTY: {} Strikes: {}/3", reason, strikes)
+            }
+            SimEvent::ScoreChanged { score, .. } => write!(f, "Score: {}", score),
+            SimEvent::GoAround {
+                plane,
+                runway,
+                reason,
+            } => match reason {
+                GoAroundReason::Instructed => write!(
+                    f,
+                    "ATC: {} go around, fly the runway {} missed approach.",
+                    plane, runway
+                ),
+                GoAroundReason::RunwayOccupied { occupant } => write!(
+                    f,
+                    "GO AROUND: {} is going around, runway {} is occupied by {}.",
+                    plane, runway, occupant
+                ),
+                GoAroundReason::RunwayClosed => write!(
+                    f,
+                    "GO AROUND: {} is going around, runway {} has closed.",
+                    plane, runway
+                ),
+            },
+            SimEvent::Emergency {
+                plane,
+                kind,
+                returning,
+            } => {
+                let call = if kind.is_distress() { "MAYDAY MAYDAY MAYDAY" } else { "PAN PAN" };
+                write!(f, "{}: {} {}", call, plane, kind)?;
+                if *returning {
+                    write!(f, ", returning to the field")?;
+                }
+                write!(f, ", requesting priority landing")?;
+                if let Some(services) = kind.services() {
+                    write!(f, " and {}", services)?;
+                }
+                write!(f, ".")
+            }
+            SimEvent::FuelDeclared {
+                plane,
+                state: FuelState::Mayday,
+                minutes,
+            } => write!(
+                f,
+                "MAYDAY MAYDAY MAYDAY: {} declaring a fuel emergency, {} minutes of fuel remaining!",
+                plane, minutes
+            ),
+            SimEvent::FuelDeclared { plane, minutes, .. } => write!(
+                f,
+                "MINIMUM FUEL: {} declaring minimum fuel, {} minutes remaining, can accept no undue delay.",
+                plane, minutes
+            ),
+            SimEvent::HoldingShort { plane, runway } => write!(
+                f,
+                "INFO: {} holding short of runway {}, requesting to cross.",
+                plane, runway
+            ),
+            SimEvent::Holding => write!(f, "ATC: Holding position."),
+            SimEvent::CommandRejected(error) => write!(f, "COMMAND ERROR: {}", error),
+            SimEvent::Info(message) => write!(f, "INFO: {}", message),
+            SimEvent::Incident { incident, total } => {
+                write!(f, "INCIDENT: {} (incidents: {})", incident, total)
+            }
+            SimEvent::GameOver {
+                final_score,
+                reason,
+            } => write!(f, "{} FINAL SCORE: {}", reason, final_score),
+            SimEvent::SessionEnded => write!(f, "Thanks for playing!"),
+        }
+    }
+}
@@ -0,0 +1,399 @@
+// --- FLIGHT MODULE ---
+// Kinematics for airborne planes. Positions are in nautical miles from the
+// airport (x east, y north), altitudes in feet above the field. A tick is one
+// minute of flight, integrated in short steps so turns and climbs are smooth.
+
+/// Simulated seconds in one game tick
+pub const SECONDS_PER_TICK: f64 = 60.0;
+/// Integration step within a tick, in seconds
+const STEP_SECONDS: f64 = 5.0;
+
+/// Feet in a nautical mile, for placing surface positions on the radar
+pub const FT_PER_NM: f64 = 6076.12;
+/// Planes beyond this range have left our airspace
+pub const AIRSPACE_RADIUS_NM: f64 = 40.0;
+/// Height arrivals descend to before asking to land
+pub const APPROACH_ALTITUDE_FT: f64 = 3000.0;
+/// Range within which an arrival at approach altitude asks to land
+pub const APPROACH_RANGE_NM: f64 = 12.0;
+/// Height departures climb to on their own
+pub const DEPARTURE_ALTITUDE_FT: f64 = 10000.0;
+/// Height departures turn on course for their route once through
+pub const DEPARTURE_TURN_ALTITUDE_FT: f64 = 3000.0;
+/// Arrivals enter up to this many degrees either side of the direction
+/// their route comes in from
+pub const ENTRY_SPREAD_DEGREES: f64 = 10.0;
+/// Speed arrivals are handed to us at
+pub const ENTRY_SPEED_KT: f64 = 250.0;
+
+/// Ticks from a landing clearance to touchdown
+pub const FINAL_APPROACH_TICKS: i32 = 2;
+
+/// Lowest altitude the controller may assign
+pub const MIN_VECTORING_ALTITUDE_FT: u32 = 2000;
+/// A plane flying direct is over the fix once this close to it
+const FIX_CAPTURE_NM: f64 = 1.0;
+
+/// Length of a holding pattern's outbound leg, in seconds
+const HOLD_LEG_SECONDS: f64 = 60.0;
+/// Speed flown in a hold unless ATC says otherwise
+const HOLD_SPEED_KT: f64 = 210.0;
+
+/// Standard rate turn, degrees per second
+const TURN_RATE: f64 = 3.0;
+/// Speed change, knots per second
+const ACCELERATION: f64 = 2.0;
+const DECELERATION: f64 = 1.0;
+
+/// What an aircraft type can do in the air
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Performance {
+    pub climb_fpm: f64,
+    pub descent_fpm: f64,
+    pub min_speed_kt: u16,
+    pub max_speed_kt: u16,
+    pub ceiling_ft: u32,
+}
+
+/// Where a plane is: range from the field and height above it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Position {
+    pub x_nm: f64,
+    pub y_nm: f64,
+    pub altitude_ft: f64,
+}
+
+/// Which targets the controller has assigned. The rest are flown by the
+/// plane's own navigation.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Assigned {
+    pub heading: bool,
+    pub altitude: bool,
+    pub speed: bool,
+}
+
+/// A fix the plane has been sent direct to
+#[derive(Debug, Clone, PartialEq)]
+pub struct DirectTo {
+    pub fix: String,
+    pub x_nm: f64,
+    pub y_nm: f64,
+}
+
+/// Where a plane is in its holding pattern
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HoldLeg {
+    /// Flying to the fix to start the hold
+    Entry,
+    /// Turning away from the fix, then flying a timed leg away from it
+    Outbound { turning: bool, remaining_s: f64 },
+    /// Turning back, then flying to the fix
+    Inbound { turning: bool },
+}
+
+/// A racetrack flown over a fix until ATC sends the plane on
+#[derive(Debug, Clone, PartialEq)]
+pub struct HoldingPattern {
+    pub fix: String,
+    pub x_nm: f64,
+    pub y_nm: f64,
+    /// Course flown toward the fix, degrees
+    pub inbound_course: f64,
+    pub right_turns: bool,
+    /// Place in the stack, 0 at the bottom
+    pub level: u32,
+    pub leg: HoldLeg,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlightState {
+    pub x_nm: f64,
+    pub y_nm: f64,
+    pub altitude_ft: f64,
+    /// Degrees, 0-360 with 0 north
+    pub heading: f64,
+    pub ground_speed_kt: f64,
+    pub vertical_speed_fpm: f64,
+    /// What the plane is turning, climbing and accelerating toward
+    pub target_heading: f64,
+    pub target_altitude_ft: f64,
+    pub target_speed_kt: f64,
+    /// Circle (left turns) instead of holding the target heading
+    pub orbiting: bool,
+    pub assigned: Assigned,
+    /// Steering for a fix rather than flying a heading
+    pub direct_to: Option<DirectTo>,
+    /// Flying a holding pattern
+    pub holding: Option<HoldingPattern>,
+}
+
+impl FlightState {
+    /// An arrival entering the airspace `range_nm` out on `bearing` from the
+    /// field, pointed at the field
+    pub fn arriving(bearing: f64, range_nm: f64, altitude_ft: f64, speed_kt: f64) -> Self {
+        let radians = bearing.to_radians();
+        let heading = normalise(bearing + 180.0);
+        FlightState {
+            x_nm: range_nm * radians.sin(),
+            y_nm: range_nm * radians.cos(),
+            altitude_ft,
+            heading,
+            ground_speed_kt: speed_kt,
+            vertical_speed_fpm: 0.0,
+            target_heading: heading,
+            target_altitude_ft: altitude_ft,
+            target_speed_kt: speed_kt,
+            orbiting: false,
+            assigned: Assigned::default(),
+            direct_to: None,
+            holding: None,
+        }
+    }
+
+    /// A departure lifting off at `x_nm`, `y_nm` on `runway_heading`
+    pub fn departing(x_nm: f64, y_nm: f64, runway_heading: f64, performance: &Performance) -> Self {
+        FlightState {
+            x_nm,
+            y_nm,
+            altitude_ft: 0.0,
+            heading: runway_heading,
+            ground_speed_kt: 160.0,
+            vertical_speed_fpm: performance.climb_fpm,
+            target_heading: runway_heading,
+            target_altitude_ft: DEPARTURE_ALTITUDE_FT.min(f64::from(performance.ceiling_ft)),
+            target_speed_kt: 250.0_f64.min(f64::from(performance.max_speed_kt)),
+            orbiting: false,
+            assigned: Assigned::default(),
+            direct_to: None,
+            holding: None,
+        }
+    }
+
+    /// Fly `heading`, cancelling any orbit, hold or direct routing
+    pub fn assign_heading(&mut self, heading: f64) {
+        self.leave_hold();
+        self.target_heading = heading;
+        self.orbiting = false;
+        self.direct_to = None;
+        self.assigned.heading = true;
+    }
+
+    pub fn assign_altitude(&mut self, altitude_ft: f64) {
+        self.target_altitude_ft = altitude_ft;
+        self.assigned.altitude = true;
+    }
+
+    pub fn assign_speed(&mut self, speed_kt: f64) {
+        self.target_speed_kt = speed_kt;
+        self.assigned.speed = true;
+    }
+
+    /// Steer for a fix. Once over it the plane resumes its own navigation.
+    pub fn proceed_direct(&mut self, fix: &str, x_nm: f64, y_nm: f64) {
+        self.leave_hold();
+        self.orbiting = false;
+        self.direct_to = Some(DirectTo {
+            fix: fix.to_string(),
+            x_nm,
+            y_nm,
+        });
+        self.assigned.heading = true;
+    }
+
+    /// Turn onto the runway heading and descend to land at `speed_kt`
+    pub fn fly_final(&mut self, runway_heading: f64, speed_kt: f64) {
+        self.assign_heading(runway_heading);
+        self.assign_altitude(0.0);
+        self.assign_speed(speed_kt);
+    }
+
+    /// Abandon the approach: climb away on the runway heading, or direct to
+    /// the missed approach fix at (`x_nm`, `y_nm`)
+    pub fn go_around(&mut self, runway_heading: f64, altitude_ft: f64, fix: Option<(&str, f64, f64)>) {
+        self.assign_heading(runway_heading);
+        self.assign_altitude(altitude_ft);
+        self.assign_speed(210.0);
+        if let Some((name, x_nm, y_nm)) = fix {
+            self.proceed_direct(name, x_nm, y_nm);
+        }
+    }
+
+    /// Fly to the fix and hold there at `altitude_ft`
+    pub fn enter_hold(&mut self, pattern: HoldingPattern, altitude_ft: f64) {
+        self.orbiting = false;
+        self.direct_to = None;
+        self.holding = Some(pattern);
+        self.assigned.heading = true;
+        self.assign_altitude(altitude_ft);
+        if !self.assigned.speed {
+            self.target_speed_kt = HOLD_SPEED_KT;
+        }
+    }
+
+    /// Stop holding. The stack altitude and hold speed no longer apply, so
+    /// the plane descends and slows on its own again.
+    fn leave_hold(&mut self) {
+        if self.holding.take().is_some() {
+            self.assigned = Assigned::default();
+        }
+    }
+
+    /// Back under the plane's own navigation
+    pub fn resume_own_navigation(&mut self) {
+        self.assigned = Assigned::default();
+        self.direct_to = None;
+        self.orbiting = false;
+    }
+
+    pub fn position(&self) -> Position {
+        Position {
+            x_nm: self.x_nm,
+            y_nm: self.y_nm,
+            altitude_ft: self.altitude_ft,
+        }
+    }
+
+    /// Distance from the field
+    pub fn range_nm(&self) -> f64 {
+        self.x_nm.hypot(self.y_nm)
+    }
+
+    /// Bearing of the plane as seen from the field
+    pub fn bearing_from_field(&self) -> f64 {
+        normalise(self.x_nm.atan2(self.y_nm).to_degrees())
+    }
+
+    /// Heading that points the plane at the field
+    pub fn bearing_to_field(&self) -> f64 {
+        normalise(self.bearing_from_field() + 180.0)
+    }
+
+    /// Fly one tick toward the targets, within the type's performance
+    pub fn advance_tick(&mut self, performance: &Performance) {
+        let mut elapsed = 0.0;
+        while elapsed < SECONDS_PER_TICK {
+            self.navigate();
+            self.step(STEP_SECONDS, performance);
+            elapsed += STEP_SECONDS;
+        }
+    }
+
+    /// Keep pointing at the fix when flying direct, or fly the hold
+    fn navigate(&mut self) {
+        if self.holding.is_some() {
+            return self.fly_hold();
+        }
+        let Some(direct) = &self.direct_to else {
+            return;
+        };
+        let dx = direct.x_nm - self.x_nm;
+        let dy = direct.y_nm - self.y_nm;
+        if dx.hypot(dy) <= FIX_CAPTURE_NM {
+            self.direct_to = None;
+            self.assigned.heading = false;
+        } else {
+            self.target_heading = normalise(dx.atan2(dy).to_degrees());
+        }
+    }
+
+    /// Steer around the racetrack: over the fix, turn outbound; fly the
+    /// timed leg; turn back and fly to the fix. Both turns go the hold's way.
+    fn fly_hold(&mut self) {
+        let heading = self.heading;
+        let Some(hold) = self.holding.as_mut() else {
+            return;
+        };
+        let (dx, dy) = (hold.x_nm - self.x_nm, hold.y_nm - self.y_nm);
+        let over_fix = dx.hypot(dy) <= FIX_CAPTURE_NM;
+        let to_fix = normalise(dx.atan2(dy).to_degrees());
+        let outbound = normalise(hold.inbound_course + 180.0);
+        let right = hold.right_turns;
+        // Aim at most 90° ahead in the hold's direction, so the short-way
+        // turn in `step` goes the right way round
+        let turn_toward = |target: f64| {
+            let remaining = if right {
+                (target - heading).rem_euclid(360.0)
+            } else {
+                -(heading - target).rem_euclid(360.0)
+            };
+            normalise(heading + remaining.clamp(-90.0, 90.0))
+        };
+
+        let (target, leg) = match hold.leg {
+            HoldLeg::Entry if over_fix => (
+                turn_toward(outbound),
+                HoldLeg::Outbound {
+                    turning: true,
+                    remaining_s: HOLD_LEG_SECONDS,
+                },
+            ),
+            HoldLeg::Entry => (to_fix, HoldLeg::Entry),
+            HoldLeg::Outbound { turning: true, remaining_s } => {
+                let turning = signed_turn(heading, outbound).abs() > 5.0;
+                (turn_toward(outbound), HoldLeg::Outbound { turning, remaining_s })
+            }
+            HoldLeg::Outbound { remaining_s, .. } if remaining_s <= 0.0 => {
+                (turn_toward(to_fix), HoldLeg::Inbound { turning: true })
+            }
+            HoldLeg::Outbound { remaining_s, .. } => (
+                outbound,
+                HoldLeg::Outbound {
+                    turning: false,
+                    remaining_s: remaining_s - STEP_SECONDS,
+                },
+            ),
+            HoldLeg::Inbound { turning: true } => {
+                let turning = signed_turn(heading, to_fix).abs() > 30.0;
+                (turn_toward(to_fix), HoldLeg::Inbound { turning })
+            }
+            HoldLeg::Inbound { .. } if over_fix => (
+                turn_toward(outbound),
+                HoldLeg::Outbound {
+                    turning: true,
+                    remaining_s: HOLD_LEG_SECONDS,
+                },
+            ),
+            HoldLeg::Inbound { .. } => (to_fix, HoldLeg::Inbound { turning: false }),
+        };
+        hold.leg = leg;
+        self.target_heading = target;
+    }
+
+    fn step(&mut self, seconds: f64, performance: &Performance) {
+        // Turn the short way at standard rate, or keep circling left
+        let max_turn = TURN_RATE * seconds;
+        let turn = if self.orbiting {
+            -max_turn
+        } else {
+            signed_turn(self.heading, self.target_heading).clamp(-max_turn, max_turn)
+        };
+        self.heading = normalise(self.heading + turn);
+
+        let altitude_error = self.target_altitude_ft - self.altitude_ft;
+        self.vertical_speed_fpm = if altitude_error > 0.0 {
+            performance.climb_fpm.min(altitude_error * 60.0 / seconds)
+        } else {
+            -(performance.descent_fpm.min(-altitude_error * 60.0 / seconds))
+        };
+        self.altitude_ft += self.vertical_speed_fpm * seconds / 60.0;
+
+        let speed_error = self.target_speed_kt - self.ground_speed_kt;
+        self.ground_speed_kt += speed_error.clamp(-DECELERATION * seconds, ACCELERATION * seconds);
+
+        let distance = self.ground_speed_kt * seconds / 3600.0;
+        let radians = self.heading.to_radians();
+        self.x_nm += distance * radians.sin();
+        self.y_nm += distance * radians.cos();
+    }
+}
+
+/// Shortest turn from `from` to `to` in degrees: positive is to the right
+pub fn signed_turn(from: f64, to: f64) -> f64 {
+    (to - from + 540.0).rem_euclid(360.0) - 180.0
+}
+
+/// Wrap a heading into (0, 360]
+pub fn normalise(heading: f64) -> f64 {
+    let wrapped = heading.rem_euclid(360.0);
+    if wrapped == 0.0 { 360.0 } else { wrapped }
+}
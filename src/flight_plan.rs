@@ -1,3 +1,4 @@
+use crate::navdata::NavDatabase;
 use rand::{seq::SliceRandom, Rng};
 
 // --- AIRLINE ENUM ---
@@ -13,7 +14,7 @@ pub enum Airline {
 
 impl Airline {
     /// Get the ICAO code for the airline
-    fn get_id(&self) -> &str {
+    pub fn get_id(&self) -> &str {
         match self {
             Airline::Southwest => "SWA",
             Airline::Delta => "DAL",
@@ -24,7 +25,7 @@ impl Airline {
     }
 
     /// Select a random airline
-    fn random() -> Self {
+    fn random(rng: &mut impl Rng) -> Self {
         let choices = [
             Airline::Southwest,
             Airline::Delta,
@@ -32,7 +33,7 @@ impl Airline {
             Airline::United,
             Airline::JetBlue,
         ];
-        *choices.choose(&mut rand::thread_rng()).unwrap()
+        *choices.choose(rng).unwrap()
     }
 }
 
@@ -45,31 +46,69 @@ pub enum FlightType {
 }
 
 impl FlightType {
-    fn random() -> Self {
+    fn random(rng: &mut impl Rng) -> Self {
         let choices = [
             FlightType::Domestic,
             FlightType::Domestic, // Weight domestic heavier
             FlightType::International,
             FlightType::Cargo,
         ];
-        *choices.choose(&mut rand::thread_rng()).unwrap()
+        *choices.choose(rng).unwrap()
     }
 }
 
 // --- WAYPOINT STRUCT ---
 // A single point in a flight path
 #[derive(Debug, Clone)]
-#[allow(dead_code)] // <-- ADD THIS LINE to suppress warnings
 pub struct Waypoint {
     pub name: String,
     pub latitude: f64,
     pub longitude: f64,
+    /// Airway flown to reach this waypoint; None for a direct leg
+    pub airway: Option<String>,
+}
+
+/// One leg of a route, waypoint to waypoint
+#[derive(Debug, Clone, PartialEq)]
+pub struct Leg {
+    pub from: String,
+    pub to: String,
+    pub airway: Option<String>,
+    pub distance_nm: f64,
+    /// Initial great-circle course, in degrees true
+    pub course: f64,
+    /// Estimated time en route at cruise speed
+    pub minutes: f64,
+}
+
+/// Mean radius of the earth, in nautical miles
+const EARTH_RADIUS_NM: f64 = 3440.065;
+/// Speed flights are planned at, for turning distance into time
+pub const CRUISE_SPEED_KT: f64 = 450.0;
+
+impl Waypoint {
+    /// Great-circle distance to `other`, in nautical miles
+    pub fn distance_nm(&self, other: &Waypoint) -> f64 {
+        let (lat1, lat2) = (self.latitude.to_radians(), other.latitude.to_radians());
+        let d_lat = lat2 - lat1;
+        let d_lon = (other.longitude - self.longitude).to_radians();
+        let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS_NM * a.sqrt().asin()
+    }
+
+    /// Initial great-circle course to `other`, in degrees true
+    pub fn course_to(&self, other: &Waypoint) -> f64 {
+        let (lat1, lat2) = (self.latitude.to_radians(), other.latitude.to_radians());
+        let d_lon = (other.longitude - self.longitude).to_radians();
+        let y = d_lon.sin() * lat2.cos();
+        let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * d_lon.cos();
+        y.atan2(x).to_degrees().rem_euclid(360.0)
+    }
 }
 
 // --- FLIGHT PLAN STRUCT ---
 // Contains all data for a single flight
 #[derive(Debug, Clone)]
-#[allow(dead_code)] // <-- ADD THIS LINE to suppress warnings
 pub struct FlightPlan {
     pub flight_id: String,
     pub airline: Airline,
@@ -81,69 +120,125 @@ pub struct FlightPlan {
 }
 
 impl FlightPlan {
-    /// Create a new, randomized flight plan
-    pub fn new() -> Self {
-        let airline = Airline::random();
-        let flight_num = rand::thread_rng().gen_range(100..=1999);
+    /// Create a new, randomized flight plan flown by one of `aircraft_types`,
+    /// arriving at or departing from `home`, routed along the airways.
+    /// None if `home` is not in `navdata`.
+    pub fn new(
+        aircraft_types: &[&str],
+        navdata: &NavDatabase,
+        home: &str,
+        arriving: bool,
+        rng: &mut impl Rng,
+    ) -> Option<Self> {
+        let airline = Airline::random(rng);
+        let flight_num = rng.gen_range(100..=1999);
         let flight_id = format!("{}{}", airline.get_id(), flight_num);
 
-        let aircraft_choices = ["B737", "A320", "B787", "A330", "E175"];
-        let aircraft_type = aircraft_choices
-            .choose(&mut rand::thread_rng())
+        let aircraft_type = aircraft_types
+            .choose(rng)
             .unwrap()
             .to_string();
 
-        let origin_choices = ["KPDX"];
-        let dest_choices = ["KSFO", "KLAS", "KMIA", "KBOS", "KDEN"];
+        let others: Vec<&str> = navdata
+            .airports()
+            .into_iter()
+            .filter(|icao| !icao.eq_ignore_ascii_case(home))
+            .collect();
+        let other = others.choose(rng).copied().unwrap_or(home).to_string();
+        let (origin, destination) = if arriving {
+            (other, home.to_string())
+        } else {
+            (home.to_string(), other)
+        };
+        let route = navdata.route(&origin, &destination)?;
 
-        let origin = origin_choices
-            .choose(&mut rand::thread_rng())
-            .unwrap()
-            .to_string();
-        let destination = dest_choices
-            .choose(&mut rand::thread_rng())
-            .unwrap()
-            .to_string();
-
-        FlightPlan {
+        Some(FlightPlan {
             flight_id,
             airline,
             aircraft_type,
             origin,
             destination,
-            flight_type: FlightType::random(),
-            route: FlightPlan::generate_random_route(),
+            flight_type: FlightType::random(rng),
+            route,
+        })
+    }
+
+    /// Every leg of the route, with its distance, course and time
+    pub fn legs(&self) -> Vec<Leg> {
+        self.route
+            .windows(2)
+            .map(|pair| {
+                let distance_nm = pair[0].distance_nm(&pair[1]);
+                Leg {
+                    from: pair[0].name.clone(),
+                    to: pair[1].name.clone(),
+                    airway: pair[1].airway.clone(),
+                    distance_nm,
+                    course: pair[0].course_to(&pair[1]),
+                    minutes: distance_nm / CRUISE_SPEED_KT * 60.0,
+                }
+            })
+            .collect()
+    }
+
+    /// Length of the route, waypoint to waypoint, in nautical miles
+    pub fn route_length_nm(&self) -> f64 {
+        self.legs().iter().map(|leg| leg.distance_nm).sum()
+    }
+
+    /// Estimated time to fly the whole route, in minutes
+    pub fn enroute_minutes(&self) -> f64 {
+        self.legs().iter().map(|leg| leg.minutes).sum()
+    }
+
+    /// Course a departure turns onto for the first waypoint after its origin
+    pub fn initial_course(&self) -> Option<f64> {
+        self.legs().first().map(|leg| leg.course)
+    }
+
+    /// Direction an arrival comes from: the bearing from its destination
+    /// back to the waypoint before it
+    pub fn inbound_bearing(&self) -> Option<f64> {
+        match self.route.as_slice() {
+            [.., before, destination] => Some(destination.course_to(before)),
+            _ => None,
         }
     }
 
-    /// Generate a simple, random route
-    fn generate_random_route() -> Vec<Waypoint> {
-        let mut route = Vec::new();
-        let point_names = ["ALPHA", "BRAVO", "CHARLIE", "DELTA", "ECHO"];
-        let num_points = rand::thread_rng().gen_range(2..=5);
-
-        for _ in 0..num_points {
-            route.push(Waypoint {
-                name: point_names
-                    .choose(&mut rand::thread_rng())
-                    .unwrap()
-                    .to_string(),
-                latitude: rand::thread_rng().gen_range(30.0..=50.0),
-                longitude: rand::thread_rng().gen_range(-120.0..=-75.0),
-            });
+    /// The route as filed, e.g. `KPDX BTG J1 OAK KSFO`: waypoints joined by
+    /// the airways between them, with consecutive legs on one airway
+    /// written once
+    pub fn route_string(&self) -> String {
+        let mut parts: Vec<&str> = Vec::new();
+        for (i, waypoint) in self.route.iter().enumerate() {
+            let airway = waypoint.airway.as_deref();
+            let next_airway = self.route.get(i + 1).and_then(|w| w.airway.as_deref());
+            if let Some(airway) = airway
+                && parts.last() != Some(&airway)
+            {
+                parts.push(airway);
+            }
+            // Where an airway carries on, its waypoints go unwritten
+            if airway.is_none() || next_airway != airway {
+                parts.push(&waypoint.name);
+            }
         }
-        route
+        parts.join(" ")
     }
 
     /// Print a formatted flight plan summary
     pub fn summary(&self) -> String {
+        let minutes = self.enroute_minutes().round() as u32;
         format!(
-            "{:<8} ({}) {} -> {} | {}",
+            "{:<8} ({}) {} -> {} | {} | {:.0} NM, ETE {}:{:02}",
             self.flight_id,
             self.aircraft_type,
             self.origin,
             self.destination,
-            format!("{:?}", self.flight_type).to_uppercase()
+            format!("{:?}", self.flight_type).to_uppercase(),
+            self.route_length_nm(),
+            minutes / 60,
+            minutes % 60
         )
     }
 }
@@ -154,6 +249,12 @@ pub struct FlightPlanManager {
     pub active_plans: Vec<FlightPlan>,
 }
 
+impl Default for FlightPlanManager {
+    fn default() -> Self {
+        FlightPlanManager::new()
+    }
+}
+
 impl FlightPlanManager {
     pub fn new() -> Self {
         FlightPlanManager {
@@ -161,20 +262,28 @@ impl FlightPlanManager {
         }
     }
 
-    /// Generate a new, unique flight plan
-    pub fn generate_new_plan(&mut self) -> FlightPlan {
+    /// Generate a new, unique flight plan to or from `home`. None if `home`
+    /// is not in `navdata`.
+    pub fn generate_new_plan(
+        &mut self,
+        aircraft_types: &[&str],
+        navdata: &NavDatabase,
+        home: &str,
+        arriving: bool,
+        rng: &mut impl Rng,
+    ) -> Option<FlightPlan> {
         // Ensure flight ID is unique (basic implementation)
-        let mut new_plan = FlightPlan::new();
+        let mut new_plan = FlightPlan::new(aircraft_types, navdata, home, arriving, rng)?;
         while self
             .active_plans
             .iter()
             .any(|p| p.flight_id == new_plan.flight_id)
         {
-            new_plan = FlightPlan::new();
+            new_plan = FlightPlan::new(aircraft_types, navdata, home, arriving, rng)?;
         }
         let plan_clone = new_plan.clone();
         self.active_plans.push(new_plan);
-        plan_clone
+        Some(plan_clone)
     }
 
     /// Remove a plan by ID
@@ -182,3 +291,47 @@ impl FlightPlanManager {
         self.active_plans.retain(|p| p.flight_id != flight_id);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn waypoint(name: &str, latitude: f64, longitude: f64) -> Waypoint {
+        Waypoint {
+            name: name.to_string(),
+            latitude,
+            longitude,
+            airway: None,
+        }
+    }
+
+    #[test]
+    fn measures_great_circle_distances() {
+        let pdx = waypoint("KPDX", 45.5887, -122.5975);
+        let sea = waypoint("KSEA", 47.4502, -122.3088);
+        assert!((pdx.distance_nm(&sea) - 112.4).abs() < 0.1);
+        assert!((sea.distance_nm(&pdx) - pdx.distance_nm(&sea)).abs() < 1e-9);
+        assert_eq!(pdx.distance_nm(&pdx), 0.0);
+
+        // A degree of longitude on the equator is 60 NM
+        let origin = waypoint("A", 0.0, 0.0);
+        let east = waypoint("B", 0.0, 1.0);
+        assert!((origin.distance_nm(&east) - 60.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn plans_no_flights_for_an_airport_missing_from_the_database() {
+        let navdata = NavDatabase::new();
+        let mut rng = StdRng::seed_from_u64(7);
+        assert!(FlightPlan::new(&["B737"], &navdata, "ZZZZ", true, &mut rng).is_none());
+        let mut manager = FlightPlanManager::new();
+        assert!(manager.generate_new_plan(&["B737"], &navdata, "ZZZZ", false, &mut rng).is_none());
+        assert!(manager.active_plans.is_empty());
+
+        let plan = FlightPlan::new(&["B737"], &navdata, "KPDX", true, &mut rng).unwrap();
+        assert_eq!(plan.destination, "KPDX");
+        assert_eq!(plan.route.last().unwrap().name, "KPDX");
+    }
+}
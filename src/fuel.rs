@@ -0,0 +1,89 @@
+use crate::aircraft::AircraftType;
+use crate::flight_plan::FlightPlan;
+use std::fmt;
+
+// --- FUEL MODULE ---
+// How much fuel a flight carries and when its crew starts to worry. Fuel is
+// planned from the route length and the type's burn rate; airborne planes
+// burn it every tick, so holding an arrival eats into its reserve.
+
+/// Final reserve every flight must land with
+const FINAL_RESERVE_MINUTES: f64 = 30.0;
+/// Fuel to fly on to an alternate airport if the destination can't take it
+const ALTERNATE_MINUTES: f64 = 30.0;
+/// Contingency fuel as a share of the trip, within these bounds
+const CONTINGENCY_SHARE: f64 = 0.05;
+const MIN_CONTINGENCY_MINUTES: f64 = 5.0;
+const MAX_CONTINGENCY_MINUTES: f64 = 15.0;
+
+/// With less endurance than this the crew declares minimum fuel: any delay
+/// and it lands on its final reserve
+pub const MINIMUM_FUEL_MINUTES: f64 = FINAL_RESERVE_MINUTES + MIN_CONTINGENCY_MINUTES;
+/// With less than this it will land with less than its final reserve, and
+/// the crew declares a MAYDAY
+pub const MAYDAY_FUEL_MINUTES: f64 = FINAL_RESERVE_MINUTES;
+
+/// How worried the crew is about fuel, in increasing order of priority
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FuelState {
+    Normal,
+    /// Can accept no undue delay
+    Minimum,
+    /// Will land with less than its final reserve: an emergency
+    Mayday,
+}
+
+impl FuelState {
+    /// The state for a plane with `minutes` of fuel left
+    pub fn for_endurance(minutes: f64) -> Self {
+        if minutes < MAYDAY_FUEL_MINUTES {
+            FuelState::Mayday
+        } else if minutes < MINIMUM_FUEL_MINUTES {
+            FuelState::Minimum
+        } else {
+            FuelState::Normal
+        }
+    }
+}
+
+/// Time to fly the planned route, in minutes
+fn trip_minutes(plan: &FlightPlan) -> f64 {
+    plan.enroute_minutes()
+}
+
+/// Contingency for the route, in minutes
+fn contingency_minutes(plan: &FlightPlan) -> f64 {
+    (trip_minutes(plan) * CONTINGENCY_SHARE).clamp(MIN_CONTINGENCY_MINUTES, MAX_CONTINGENCY_MINUTES)
+}
+
+/// Fuel loaded at the gate: the trip, contingency, alternate and final
+/// reserve
+pub fn departure_fuel_lb(plan: &FlightPlan, aircraft: &AircraftType) -> f64 {
+    let minutes = trip_minutes(plan) + contingency_minutes(plan) + ALTERNATE_MINUTES + FINAL_RESERVE_MINUTES;
+    minutes * aircraft.fuel_burn_lb_per_min
+}
+
+/// Fuel left when an arrival reaches our airspace: the trip is flown, so
+/// what remains is contingency, alternate, final reserve and
+/// `extra_minutes` the crew chose to carry
+pub fn arrival_fuel_lb(plan: &FlightPlan, aircraft: &AircraftType, extra_minutes: f64) -> f64 {
+    let minutes = contingency_minutes(plan) + ALTERNATE_MINUTES + FINAL_RESERVE_MINUTES + extra_minutes;
+    minutes * aircraft.fuel_burn_lb_per_min
+}
+
+/// Fuel left when a diverting flight reaches us: its alternate fuel and
+/// any extra went on getting here
+pub fn diversion_fuel_lb(plan: &FlightPlan, aircraft: &AircraftType) -> f64 {
+    let minutes = contingency_minutes(plan) + FINAL_RESERVE_MINUTES;
+    minutes * aircraft.fuel_burn_lb_per_min
+}
+
+impl fmt::Display for FuelState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FuelState::Normal => write!(f, "normal fuel"),
+            FuelState::Minimum => write!(f, "minimum fuel"),
+            FuelState::Mayday => write!(f, "MAYDAY fuel"),
+        }
+    }
+}
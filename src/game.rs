@@ -1,40 +1,235 @@
 // Use `crate::` to import from other files in our project
-use crate::airport::Airport;
-use crate::flight_plan::FlightPlanManager;
+use crate::aircraft::AircraftDatabase;
+use crate::airport::{Airport, GateSize, Movement, Runway, RunwayOperation};
+use crate::command::{Command, ParseError};
+use crate::emergency::{self, Emergency, EmergencyError, EmergencyKind};
+use crate::event::{Clearance, GameOverReason, GoAroundReason, Incident, Incursion, IncursionCause, PenaltyReason, SimEvent};
+use crate::flight::{self, FlightState, Position};
+use crate::flight_plan::{self, FlightPlan, FlightPlanManager};
+use crate::fuel::{self, FuelState};
+use crate::ground::{GroundLayout, GroundPosition, TaxiRoute, TaxiTarget};
 use crate::plane::{Plane, PlaneStatus};
-use rand::Rng;
+use crate::metar::{Metar, MetarError, ObservationTime};
+use crate::navdata::NavDatabase;
+use crate::replay::RecordedInput;
+use crate::scheduler::Scheduler;
+use crate::separation::{self, IncursionSeverity};
+use crate::sequence::ArrivalSequence;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
 
 // --- GAME MODULE ---
 // Contains the main game state and logic
 
+/// Chance each tick of a new flight, arriving or departing
+const SPAWN_CHANCE: f64 = 0.1;
+/// Share of new flights that are arrivals
+const ARRIVAL_SHARE: f64 = 0.5;
+/// Arrivals are listed on the radar this many ticks before they enter
+const INBOUND_NOTICE_TICKS: u64 = 10;
+
+/// An arrival flying its route from its origin, not yet in our airspace
+#[derive(Debug, Clone)]
+pub struct Inbound {
+    /// Tick it crosses into the airspace
+    pub entry_tick: u64,
+    pub plane: Plane,
+}
+
+/// Running totals for the session, used for end-of-game summaries
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SessionStats {
+    pub pushbacks: u32,
+    pub takeoffs: u32,
+    pub landings: u32,
+    pub penalties: u32,
+    pub go_arounds: u32,
+    pub emergencies: u32,
+}
+
+impl SessionStats {
+    /// Runway movements: every takeoff and landing
+    pub fn movements(&self) -> u32 {
+        self.takeoffs + self.landings
+    }
+}
+
 pub struct Game {
     pub score: i32,
     pub penalty_strikes: u32,
+    /// Losses of separation this session. Unlike strikes these never reset.
+    pub incidents: u32,
+    /// Every runway incursion this session, also counted as incidents
+    pub incursions: Vec<Incursion>,
+    pub stats: SessionStats,
     pub airport: Airport,
+    /// Performance of every aircraft type in service
+    pub aircraft: AircraftDatabase,
+    /// Airports, navaids and airways flight plans are routed along
+    pub navdata: NavDatabase,
     pub planes: Vec<Plane>,
+    /// Arrivals en route from their origins, in the order they were filed
+    pub inbound: Vec<Inbound>,
+    /// The order arrivals are to land in
+    pub sequence: ArrivalSequence,
+    /// Landing and takeoff slots and advisories, when turned on
+    pub scheduler: Option<Scheduler>,
     pub flight_plan_manager: FlightPlanManager, // Field exists
     game_over: bool,
+    /// Number of ticks simulated so far
+    tick: u64,
+    /// Seed the session RNG was created from
+    seed: u64,
+    /// Every random decision in the simulation draws from this
+    rng: StdRng,
+    /// Events produced since the frontend last drained them
+    events: Vec<SimEvent>,
+    /// Every command line entered, for recording replays
+    history: Vec<RecordedInput>,
+    /// Pairs of planes currently too close, so each conflict is reported once
+    conflicts: Vec<(String, String)>,
+    /// Pairs of planes currently taxiing head-on at each other
+    ground_conflicts: Vec<(String, String)>,
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Game::new()
+    }
 }
 
 impl Game {
+    /// Start a session with a fresh random seed
     pub fn new() -> Self {
+        Game::with_seed(rand::random())
+    }
+
+    /// Start a session from a fixed seed. The same seed and the same
+    /// command sequence always reproduce the same session.
+    pub fn with_seed(seed: u64) -> Self {
+        Game::with_airport(seed, Airport::new())
+    }
+
+    /// Start a seeded session at a specific airport layout
+    pub fn with_airport(seed: u64, airport: Airport) -> Self {
+        Game::with_aircraft(seed, airport, AircraftDatabase::new())
+    }
+
+    /// Start a seeded session at a specific airport layout, flying the
+    /// aircraft types in `aircraft`
+    pub fn with_aircraft(seed: u64, airport: Airport, aircraft: AircraftDatabase) -> Self {
+        Game::with_navdata(seed, airport, aircraft, NavDatabase::new())
+    }
+
+    /// Start a seeded session at a specific airport layout, flying the
+    /// aircraft types in `aircraft` along the routes in `navdata`
+    pub fn with_navdata(seed: u64, mut airport: Airport, aircraft: AircraftDatabase, navdata: NavDatabase) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        airport.plan_weather(&mut rng, 0);
         let mut flight_plan_manager = FlightPlanManager::new();
-        // Generate a couple of initial flight plans
-        let plan1 = flight_plan_manager.generate_new_plan();
-        let plan2 = flight_plan_manager.generate_new_plan();
+        // Generate a couple of initial flight plans, both departures
+        let types = aircraft.designators();
+        let plans: Vec<FlightPlan> = (0..2)
+            .filter_map(|_| flight_plan_manager.generate_new_plan(&types, &navdata, &airport.icao, false, &mut rng))
+            .collect();
+        let new_planes: Vec<Plane> = plans.into_iter().map(|plan| Plane::new(plan, &aircraft, &mut rng)).collect();
+        let mut planes = Vec::new();
+        for mut plane in new_planes {
+            if park_at_gate(&mut plane, &mut airport, &mut rng) {
+                planes.push(plane);
+            } else {
+                flight_plan_manager.remove_plan(&plane.id);
+            }
+        }
 
-        // --- THIS IS THE CORRECTED STRUCT INITIALIZATION ---
-        Game {
+        let mut game = Game {
             score: 0,
             penalty_strikes: 0,
-            airport: Airport::new(),
-            planes: vec![
-                Plane::new(plan1), // Use plans to create planes
-                Plane::new(plan2),
-            ],
-            flight_plan_manager, // <-- THE FIX: Added this missing field
+            incidents: 0,
+            incursions: Vec::new(),
+            stats: SessionStats::default(),
+            airport,
+            aircraft,
+            navdata,
+            planes, // Use plans to create planes
+            inbound: Vec::new(),
+            sequence: ArrivalSequence::new(),
+            scheduler: None,
+            flight_plan_manager,
             game_over: false,
+            tick: 0,
+            seed,
+            rng,
+            events: Vec::new(),
+            history: Vec::new(),
+            conflicts: Vec::new(),
+            ground_conflicts: Vec::new(),
+        };
+
+        // Arrivals already on their way: filed at the usual rate over as
+        // long as the longest route takes to fly
+        let flight_minutes = game.navdata.longest_route_nm(&game.airport.icao) / flight_plan::CRUISE_SPEED_KT * 60.0;
+        for minutes_ago in (1..=flight_minutes.ceil() as u64).rev() {
+            if game.rng.gen_bool(SPAWN_CHANCE * ARRIVAL_SHARE) {
+                game.file_arrival(minutes_ago);
+            }
         }
+        game
+    }
+
+    /// The seed this session was started from
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Number of ticks simulated so far
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// Every command line and METAR given so far, with the tick it arrived at
+    pub fn history(&self) -> &[RecordedInput] {
+        &self.history
+    }
+
+    /// The airport weather right now, as a METAR
+    pub fn metar(&self) -> Metar {
+        self.airport.metar(ObservationTime::at_tick(self.tick))
+    }
+
+    /// Set the airport weather from a METAR, e.g. one pasted by a scenario
+    pub fn set_metar(&mut self, text: &str) -> Result<(), MetarError> {
+        self.airport.set_metar(text, self.tick, &mut self.events)?;
+        self.history.push(RecordedInput::Metar {
+            tick: self.tick,
+            metar: text.trim().to_string(),
+        });
+        Ok(())
+    }
+
+    /// A crew declares `kind`, e.g. from a scenario script
+    pub fn declare_emergency(&mut self, plane: &str, kind: EmergencyKind) -> Result<(), EmergencyError> {
+        self.start_emergency(plane, kind)?;
+        self.history.push(RecordedInput::Emergency {
+            tick: self.tick,
+            plane: plane.to_uppercase(),
+            kind,
+        });
+        Ok(())
+    }
+
+    /// Turn on the arrival and departure managers: landing and takeoff
+    /// slots, advisories and the delay baseline
+    pub fn enable_scheduler(&mut self) {
+        let mut scheduler = Scheduler::new();
+        scheduler.update(&self.airport, &self.planes, &self.sequence, self.tick);
+        self.scheduler = Some(scheduler);
+    }
+
+    /// Take every event produced since the last call, oldest first
+    pub fn drain_events(&mut self) -> Vec<SimEvent> {
+        std::mem::take(&mut self.events)
     }
 
     /// The main game "tick". Update all entities.
@@ -42,17 +237,36 @@ impl Game {
         if self.game_over {
             return;
         }
+        self.tick += 1;
 
-        // Update all planes
+        // Update all planes, remembering where the airborne ones started
+        let before: Vec<Option<Position>> = self
+            .planes
+            .iter()
+            .map(|p| p.flight.as_ref().map(FlightState::position))
+            .collect();
         for plane in self.planes.iter_mut() {
-            plane.update();
+            plane.update(&self.airport.ground, &mut self.rng, &mut self.events);
+        }
+        self.check_runway_crossings();
+        self.check_finals();
+        self.check_fuel();
+        if self.game_over {
+            return;
+        }
+        self.check_ground_conflicts();
+        self.check_separation(&before);
+        if self.game_over {
+            return;
         }
 
-        // Remove planes that are at the gate (finished)
+        // Remove planes that are at the gate or have left our airspace
         self.planes.retain(|p| {
-            if p.status == PlaneStatus::AtGate {
+            if matches!(p.status, PlaneStatus::AtGate | PlaneStatus::HandedOff) {
                 // If plane is done, remove its flight plan from the manager
+                // and free its gate
                 self.flight_plan_manager.remove_plan(&p.id);
+                self.airport.release_gate(&p.id);
                 false
             } else {
                 true
@@ -60,38 +274,493 @@ impl Game {
         });
 
         // Occasionally spawn new planes
-        if rand::thread_rng().gen_bool(0.1) { // 10% chance each tick
+        if self.rng.gen_bool(SPAWN_CHANCE) {
             self.spawn_plane();
         }
+        self.enter_airspace();
+
+        // Once in a while a crew has a bad day
+        if self.rng.gen_bool(emergency::CHANCE_PER_TICK) {
+            self.random_emergency();
+        }
+
+        // Weather drifts toward the active front every tick
+        self.airport.update_weather(&mut self.rng, self.tick, &mut self.events);
+
+        self.settle_holds();
+        self.sequence.update(&self.planes);
+        self.stack_arrivals();
+        if let Some(scheduler) = &mut self.scheduler {
+            scheduler.update(&self.airport, &self.planes, &self.sequence, self.tick);
+        }
+    }
+
+    /// Planes in a hold step down the stack as the levels below them empty
+    fn settle_holds(&mut self) {
+        for fix in &self.airport.fixes {
+            let Some(hold) = &fix.hold else { continue };
+            let mut stack: Vec<&mut FlightState> = self
+                .planes
+                .iter_mut()
+                .filter_map(|p| p.flight.as_mut())
+                .filter(|f| f.holding.as_ref().is_some_and(|h| h.fix == fix.name))
+                .collect();
+            stack.sort_by_key(|f| f.holding.as_ref().map_or(0, |h| h.level));
+            for (level, flight) in (0..).zip(stack) {
+                let Some(holding) = flight.holding.as_mut() else { continue };
+                if holding.level > level {
+                    holding.level = level;
+                    flight.assign_altitude(f64::from(hold.altitude_ft(level)));
+                }
+            }
+        }
+    }
+
+    /// Arrivals flying their own descent level off 1000 ft apart above any
+    /// already waiting to land, so that ones left alone don't all meet at
+    /// the approach altitude. Like a hold, each keeps its level and steps
+    /// down as the ones below land; newcomers join at the top.
+    fn stack_arrivals(&mut self) {
+        let waiting = self.planes.iter().filter(|p| p.status == PlaneStatus::ReadyToLand).count() as u32;
+        let mut stack: Vec<&mut Plane> = Vec::new();
+        for plane in &mut self.planes {
+            if plane.status == PlaneStatus::InAir {
+                stack.push(plane);
+            } else {
+                plane.arrival_level = None;
+            }
+        }
+        stack.sort_by_key(|p| p.arrival_level.unwrap_or(u32::MAX));
+        for (level, plane) in (waiting..).zip(stack) {
+            if plane.arrival_level.is_none_or(|l| l > level) {
+                plane.arrival_level = Some(level);
+            }
+        }
+    }
+
+    /// Sweep every pair of airborne planes along the paths they flew this
+    /// tick. A new loss of separation is an incident; a collision ends the
+    /// session.
+    fn check_separation(&mut self, before: &[Option<Position>]) {
+        let tracks: Vec<(String, (Option<Position>, Position))> = self
+            .planes
+            .iter()
+            .zip(before)
+            .filter_map(|(plane, start)| {
+                let flight = plane.flight.as_ref()?;
+                Some((plane.id.clone(), (*start, flight.position())))
+            })
+            .collect();
+
+        let mut conflicts = Vec::new();
+        for (i, (a, track_a)) in tracks.iter().enumerate() {
+            for (b, track_b) in &tracks[i + 1..] {
+                let proximity = separation::closest_approach(*track_a, *track_b);
+                let planes = (a.clone(), b.clone());
+                if proximity.is_collision() {
+                    self.record_incident(Incident::Collision {
+                        planes: planes.clone(),
+                        proximity,
+                    });
+                    self.end_game(GameOverReason::MidAirCollision { planes });
+                    return;
+                }
+                if proximity.is_loss_of_separation() {
+                    if !self.conflicts.contains(&planes) {
+                        self.record_incident(Incident::LossOfSeparation {
+                            planes: planes.clone(),
+                            proximity,
+                        });
+                    }
+                    conflicts.push(planes);
+                }
+            }
+        }
+        self.conflicts = conflicts;
+    }
+
+    /// Log pilots who crossed a runway without a clearance, then note who
+    /// is crossing each runway, and who is on final for it, so it counts
+    /// as occupied
+    fn check_runway_crossings(&mut self) {
+        let deviations: Vec<(String, String)> = self
+            .planes
+            .iter_mut()
+            .filter_map(|p| Some((p.id.clone(), p.ground.as_mut()?.deviation.take()?)))
+            .collect();
+        for (plane, runway) in deviations {
+            let Some(severity) = self.airport.get_runway(&runway).map(|r| r.incursion_severity(self.tick)) else {
+                continue;
+            };
+            self.record_incursion(Incursion {
+                plane,
+                runway,
+                severity,
+                cause: IncursionCause::PilotDeviation,
+            });
+        }
+
+        let layout = &self.airport.ground;
+        for runway in &mut self.airport.runways {
+            runway.crossing = self
+                .planes
+                .iter()
+                .find(|p| p.ground.as_ref().is_some_and(|g| g.is_crossing(layout, &runway.id)))
+                .map(|p| p.id.clone());
+            runway.on_final = self
+                .planes
+                .iter()
+                .find(|p| p.status == PlaneStatus::OnFinal && p.runway.as_deref() == Some(runway.id.as_str()))
+                .map(|p| p.id.clone());
+        }
+    }
+
+    /// An airborne plane with empty tanks is an accident that ends the session
+    fn check_fuel(&mut self) {
+        let dry = self
+            .planes
+            .iter()
+            .find(|p| p.flight.is_some() && p.fuel_lb <= 0.0)
+            .map(|p| p.id.clone());
+        if let Some(plane) = dry {
+            self.record_incident(Incident::FuelExhaustion { plane: plane.clone() });
+            self.end_game(GameOverReason::FuelExhaustion { plane });
+        }
+    }
+
+    /// Planes on final land when their approach is flown, or go around if
+    /// the runway has closed or someone else is on it
+    fn check_finals(&mut self) {
+        let mut go_arounds = Vec::new();
+        let mut landings = Vec::new();
+        for plane in &mut self.planes {
+            if plane.status != PlaneStatus::OnFinal {
+                continue;
+            }
+            let Some(runway) = plane.runway.as_deref().and_then(|r| self.airport.get_runway(r)) else {
+                continue;
+            };
+            if !runway.is_open {
+                go_arounds.push((plane.id.clone(), GoAroundReason::RunwayClosed));
+            } else if let Some(occupant) = runway.occupant(self.tick)
+                && occupant != plane.id
+            {
+                let occupant = occupant.to_string();
+                go_arounds.push((plane.id.clone(), GoAroundReason::RunwayOccupied { occupant }));
+            } else {
+                plane.timer -= 1;
+                if plane.timer <= 0 {
+                    landings.push(plane.id.clone());
+                }
+            }
+        }
+        for (plane, reason) in go_arounds {
+            self.go_around(&plane, reason);
+        }
+        for plane in landings {
+            self.touch_down(&plane);
+        }
+    }
+
+    /// A plane on final lands: it holds the runway while it rolls out, then
+    /// vacates at the exit nearest its gate (or the nearest free one) and
+    /// taxis in. With no gate free it waits on the ramp.
+    fn touch_down(&mut self, id: &str) {
+        let Some(plane) = find_plane(&mut self.planes, id) else {
+            return;
+        };
+        let Some(runway) = plane.runway.take() else {
+            return;
+        };
+        clear_final(&mut self.airport, &plane.id, &runway);
+        record_movement(&mut self.airport, plane, &runway, RunwayOperation::Landing, self.tick);
+        if let Some(scheduler) = &mut self.scheduler {
+            scheduler.record_movement(&plane.id, RunwayOperation::Landing, self.tick);
+        }
+        // An emergency is over once it is down
+        let emergency = plane.emergency.take();
+        if emergency.is_some_and(|e| e.kind == EmergencyKind::HydraulicFailure)
+            && let Some(runway) = self.airport.get_runway_mut(&runway)
+        {
+            // Stopped on the runway, waiting for a tug
+            runway.extend_occupancy(emergency::TOW_TICKS);
+        }
+        plane.status = PlaneStatus::TaxiingToGate;
+        plane.flight = None;
+        let gates: Vec<&str> = match &plane.gate {
+            Some(gate) => vec![gate.as_str()],
+            None => self.airport.free_gates(plane.gate_size()).map(|g| g.id.as_str()).collect(),
+        };
+        let layout = &self.airport.ground;
+        let arrival = arrival_route(layout, &runway, &gates)
+            .or_else(|| arrival_route(layout, &runway, &layout.ramp_spots(&self.airport.gates)));
+        match arrival {
+            Some((exit, route)) => {
+                let gate = route.nodes.back().filter(|n| gates.contains(&n.as_str())).cloned();
+                let mut position = GroundPosition::at(&exit);
+                position.follow(route);
+                plane.ground = Some(position);
+                if let Some(gate) = &gate {
+                    self.airport.assign_gate(&plane.id, gate);
+                }
+                plane.gate = gate;
+            }
+            None => plane.timer = 10, // 10 ticks to taxi to gate
+        }
+        self.events.push(SimEvent::PlaneStateChanged {
+            plane: plane.id.clone(),
+            from: PlaneStatus::OnFinal,
+            to: PlaneStatus::TaxiingToGate,
+        });
+        let plane = plane.id.clone();
+        self.stats.landings += 1;
+        match emergency {
+            Some(emergency) if !emergency.services_ready() => {
+                self.add_score(10);
+                if let Some(services) = emergency.kind.services() {
+                    self.add_penalty(PenaltyReason::ServicesNotAlerted { plane, services });
+                }
+            }
+            // Full points, and a bonus for getting an emergency down quickly
+            Some(emergency) => self.add_score(10 + emergency.landing_bonus(self.tick)),
+            None => self.add_score(10),
+        }
+    }
+
+    /// Send a plane on final around: it flies its runway's published missed
+    /// approach, then rejoins the arrivals
+    fn go_around(&mut self, id: &str, reason: GoAroundReason) {
+        let Some(plane) = find_plane(&mut self.planes, id) else {
+            return;
+        };
+        let (Some(runway_id), Some(flight)) = (plane.runway.take(), plane.flight.as_mut()) else {
+            return;
+        };
+        clear_final(&mut self.airport, &plane.id, &runway_id);
+        if let Some(runway) = self.airport.get_runway(&runway_id) {
+            let missed = &runway.missed_approach;
+            let fix = missed.fix.as_deref().and_then(|f| self.airport.get_fix(f));
+            let fix = fix.map(|f| {
+                let (x_nm, y_nm) = f.position();
+                (f.name.as_str(), x_nm, y_nm)
+            });
+            flight.go_around(f64::from(runway.heading), f64::from(missed.altitude_ft), fix);
+        }
+        plane.status = PlaneStatus::MissedApproach;
+        plane.timer = 0;
+        self.stats.go_arounds += 1;
+        self.events.push(SimEvent::GoAround {
+            plane: plane.id.clone(),
+            runway: runway_id,
+            reason,
+        });
+    }
+
+    /// Pick an airborne plane and give it an emergency that fits its phase
+    /// of flight. Diversions arrive as new planes instead.
+    fn random_emergency(&mut self) {
+        let candidates: Vec<(String, Vec<EmergencyKind>)> = self
+            .planes
+            .iter()
+            .filter(|p| p.emergency.is_none() && p.flight.is_some())
+            .map(|p| {
+                let kinds: Vec<EmergencyKind> = EmergencyKind::ALL
+                    .into_iter()
+                    .filter(|k| *k != EmergencyKind::Diversion && k.can_happen(p.status))
+                    .collect();
+                (p.id.clone(), kinds)
+            })
+            .filter(|(_, kinds)| !kinds.is_empty())
+            .collect();
+        let Some((plane, kinds)) = candidates.choose(&mut self.rng) else {
+            return;
+        };
+        let kind = *kinds.choose(&mut self.rng).unwrap();
+        // Only kinds that can happen to the plane were picked
+        let _ = self.start_emergency(plane, kind);
+    }
+
+    /// Put `kind` on `id`: departures turn back, and a bird strike closes
+    /// the runway it left from for a sweep
+    fn start_emergency(&mut self, id: &str, kind: EmergencyKind) -> Result<(), EmergencyError> {
+        let Some(plane) = find_plane(&mut self.planes, id) else {
+            return Err(EmergencyError::UnknownPlane(id.to_uppercase()));
+        };
+        let returning = plane.status == PlaneStatus::Departing;
+        plane.declare_emergency(kind, self.tick, &mut self.events)?;
+        let plane = plane.id.clone();
+        self.stats.emergencies += 1;
+        self.events.push(SimEvent::Emergency {
+            plane: plane.clone(),
+            kind,
+            returning,
+        });
+
+        if kind == EmergencyKind::BirdStrike {
+            let departed_from = self.airport.runways.iter_mut().find(|r| {
+                r.last_movement
+                    .as_ref()
+                    .is_some_and(|m| m.plane == plane && m.operation == RunwayOperation::Takeoff)
+            });
+            if let Some(runway) = departed_from {
+                runway.hold_for_vehicle(emergency::SWEEP_VEHICLE, self.tick + emergency::SWEEP_TICKS);
+                let message = format!(
+                    "runway {} closed for {} ticks, sweeping for debris after {}'s bird strike.",
+                    runway.id,
+                    emergency::SWEEP_TICKS,
+                    plane
+                );
+                self.events.push(SimEvent::Info(message));
+            }
+        }
+        Ok(())
+    }
+
+    /// Two planes that rolled along the same segment in opposite directions
+    /// this tick have met head-on
+    fn check_ground_conflicts(&mut self) {
+        let mut conflicts = Vec::new();
+        for (i, a) in self.planes.iter().enumerate() {
+            let Some(ground_a) = &a.ground else { continue };
+            for b in &self.planes[i + 1..] {
+                let Some(ground_b) = &b.ground else { continue };
+                let head_on = ground_a
+                    .recent
+                    .iter()
+                    .find(|(from, to)| ground_b.recent.iter().any(|(f, t)| f == to && t == from));
+                if let Some((from, to)) = head_on {
+                    let taxiway = self.airport.ground.taxiway_between(from, to).map(|t| t.name.clone());
+                    conflicts.push(((a.id.clone(), b.id.clone()), taxiway.unwrap_or_default()));
+                }
+            }
+        }
 
-        // Occasionally change weather
-        if rand::thread_rng().gen_bool(0.05) { // 5% chance each tick
-            self.airport.update_weather();
+        let mut pairs = Vec::new();
+        for (planes, taxiway) in conflicts {
+            if !self.ground_conflicts.contains(&planes) {
+                self.record_incident(Incident::HeadOnTaxi {
+                    planes: planes.clone(),
+                    taxiway,
+                });
+            }
+            pairs.push(planes);
         }
+        self.ground_conflicts = pairs;
     }
 
     fn spawn_plane(&mut self) {
-        // Use the manager to create a new plan
-        let new_plan = self.flight_plan_manager.generate_new_plan();
-        let mut new_plane = Plane::new(new_plan); // Correctly passes FlightPlan
-        
-        // 50/50 chance to spawn arriving or departing
-        if rand::thread_rng().gen_bool(0.5) {
-            new_plane.status = PlaneStatus::InAir;
-            new_plane.timer = rand::thread_rng().gen_range(10..=20); // Time until ready to land
-            println!(
-                "NEW PLANE: {} is approaching ({} -> {}), will be ready to land soon.",
-                new_plane.id, new_plane.flight_plan.origin, new_plane.flight_plan.destination
-            );
-        } else {
-            println!(
-                "NEW PLANE: {} is at the gate ({} -> {}), beginning boarding.",
-                new_plane.id, new_plane.flight_plan.origin, new_plane.flight_plan.destination
-            );
+        if self.rng.gen_bool(ARRIVAL_SHARE) {
+            // Just departed its origin; it reaches us once its route is flown
+            return self.file_arrival(0);
+        }
+
+        // Use the manager to create a new plan. An airport the navigation
+        // database doesn't know has no traffic.
+        let Some(new_plan) = self.flight_plan_manager.generate_new_plan(
+            &self.aircraft.designators(),
+            &self.navdata,
+            &self.airport.icao,
+            false,
+            &mut self.rng,
+        ) else {
+            return;
+        };
+        let mut new_plane = Plane::new(new_plan, &self.aircraft, &mut self.rng);
+        if !park_at_gate(&mut new_plane, &mut self.airport, &mut self.rng) {
+            // No room on the ramp: the flight never comes
+            self.flight_plan_manager.remove_plan(&new_plane.id);
+            self.events.push(SimEvent::Info(format!(
+                "no {} gate free for {}, flight cancelled.",
+                new_plane.gate_size(),
+                new_plane.id
+            )));
+            return;
         }
+        self.events.push(SimEvent::PlaneSpawned {
+            plane: new_plane.id.clone(),
+            origin: new_plane.flight_plan.origin.clone(),
+            destination: new_plane.flight_plan.destination.clone(),
+            arriving: false,
+        });
         self.planes.push(new_plane);
     }
 
+    /// File an arrival that left its origin `minutes_ago`. It enters the
+    /// airspace when its route, flown at cruise speed, reaches the edge.
+    fn file_arrival(&mut self, minutes_ago: u64) {
+        let Some(plan) = self.flight_plan_manager.generate_new_plan(
+            &self.aircraft.designators(),
+            &self.navdata,
+            &self.airport.icao,
+            true,
+            &mut self.rng,
+        ) else {
+            return;
+        };
+        let mut plane = Plane::new(plan, &self.aircraft, &mut self.rng);
+        let to_edge_nm = (plane.flight_plan.route_length_nm() - flight::AIRSPACE_RADIUS_NM).max(0.0);
+        let minutes_left = to_edge_nm / flight_plan::CRUISE_SPEED_KT * 60.0 - minutes_ago as f64;
+        if minutes_left < 0.0 {
+            // Already here before the session began
+            self.flight_plan_manager.remove_plan(&plane.id);
+            return;
+        }
+        // It crosses the edge part way through a tick and flies the rest
+        // of that tick inside
+        let entry_tick = self.tick + minutes_left.ceil() as u64;
+        let inside_nm = (minutes_left.ceil() - minutes_left) * flight::ENTRY_SPEED_KT / 60.0;
+
+        // High and fast, from the direction its route comes in from (give
+        // or take, so arrivals on one route don't all meet at one spot)
+        plane.status = PlaneStatus::InAir;
+        plane.timer = 0;
+        let spread = self.rng.gen_range(-flight::ENTRY_SPREAD_DEGREES..flight::ENTRY_SPREAD_DEGREES);
+        let bearing = match plane.flight_plan.inbound_bearing() {
+            Some(bearing) => bearing + spread,
+            None => self.rng.gen_range(0.0..360.0),
+        };
+        let range = flight::AIRSPACE_RADIUS_NM - inside_nm;
+        let altitude = f64::from(self.rng.gen_range(8..=11) * 1000);
+        plane.flight = Some(FlightState::arriving(bearing, range, altitude, flight::ENTRY_SPEED_KT));
+        // The trip is behind it; what's left is reserve plus whatever
+        // extra the crew chose to carry. A diversion has burned its
+        // alternate fuel and extra getting here.
+        let extra_minutes = self.rng.gen_range(0.0..15.0);
+        if self.rng.gen_bool(emergency::DIVERSION_CHANCE) {
+            plane.emergency = Some(Emergency::new(EmergencyKind::Diversion, entry_tick));
+            plane.fuel_lb = fuel::diversion_fuel_lb(&plane.flight_plan, &plane.aircraft);
+        } else {
+            plane.fuel_lb = fuel::arrival_fuel_lb(&plane.flight_plan, &plane.aircraft, extra_minutes);
+        }
+        self.inbound.push(Inbound { entry_tick, plane });
+    }
+
+    /// Inbound arrivals whose time has come enter the airspace
+    fn enter_airspace(&mut self) {
+        let tick = self.tick;
+        let (entering, inbound) = std::mem::take(&mut self.inbound)
+            .into_iter()
+            .partition(|i| i.entry_tick <= tick);
+        self.inbound = inbound;
+        for Inbound { plane, .. } in entering {
+            self.events.push(SimEvent::PlaneSpawned {
+                plane: plane.id.clone(),
+                origin: plane.flight_plan.origin.clone(),
+                destination: plane.flight_plan.destination.clone(),
+                arriving: true,
+            });
+            if let Some(emergency) = plane.emergency {
+                self.stats.emergencies += 1;
+                self.events.push(SimEvent::Emergency {
+                    plane: plane.id.clone(),
+                    kind: emergency.kind,
+                    returning: false,
+                });
+            }
+            self.planes.push(plane);
+        }
+    }
+
     /// Print the current game state to the console
     pub fn draw(&self) {
         if self.game_over {
@@ -99,185 +768,1957 @@ impl Game {
         }
         
         println!("\n-----------------------------------------------------");
-        println!("SCORE: {} | PENALTIES: {}/3 | WEATHER: {:?}", self.score, self.penalty_strikes, self.airport.weather);
+        println!("{} - {}", self.airport.icao, self.airport.name);
+        println!(
+            "SCORE: {} | PENALTIES: {}/3 | INCIDENTS: {} | SEED: {}",
+            self.score, self.penalty_strikes, self.incidents, self.seed
+        );
+        println!("METAR {} ({})", self.metar(), self.airport.weather.category());
+        println!("{}", self.airport.taf(self.tick));
         
+        let emergencies: Vec<&Plane> = self.planes.iter().filter(|p| p.emergency.is_some()).collect();
+        if !emergencies.is_empty() {
+            println!("\n*** EMERGENCIES ***");
+            for plane in emergencies {
+                let Some(emergency) = plane.emergency else { continue };
+                let services = match emergency.kind.services() {
+                    Some(services) if emergency.services_alerted => format!(" | {} standing by", services),
+                    Some(services) => format!(" | needs {} (alert {})", services, plane.id),
+                    None => "".to_string(),
+                };
+                println!(
+                    "  ! {} {} ({} ticks ago) | needs {} ft of runway{}",
+                    plane.id,
+                    emergency.kind.to_string().to_uppercase(),
+                    self.tick - emergency.declared_tick,
+                    plane.required_runway_ft(),
+                    services
+                );
+            }
+        }
+
         println!("\n--- RUNWAYS ---");
         for runway in &self.airport.runways {
             let status = if runway.is_open { "OPEN" } else { "CLOSED" };
-            println!("  - Runway {}: {}", runway.id, status);
+            let ils = if runway.ils { ", ILS" } else { "" };
+            let wind = runway.wind_components(&self.airport.weather.wind);
+            let along = if wind.headwind >= 0.0 { "HEAD" } else { "TAIL" };
+            let side = if wind.crosswind >= 0.0 { "R" } else { "L" };
+            let mins = if self.airport.weather.meets(runway.approach_minimums()) { "" } else { " | BELOW LDG MINS" };
+            let occupied = match runway.occupant(self.tick) {
+                Some(plane) if runway.is_lined_up(plane) => format!(" | {} LINED UP", plane),
+                Some(plane) if runway.crossing.as_deref() == Some(plane) => format!(" | {} CROSSING", plane),
+                // Listed with the finals below
+                Some(plane) if runway.on_final.as_deref() == Some(plane) => "".to_string(),
+                Some(plane) => format!(" | OCCUPIED BY {}", plane),
+                None => "".to_string(),
+            };
+            let finals: String = self
+                .planes
+                .iter()
+                .filter(|p| p.status == PlaneStatus::OnFinal && p.runway.as_deref() == Some(runway.id.as_str()))
+                .map(|p| format!(" | {} ON FINAL", p.id))
+                .collect();
+            println!(
+                "  - Runway {} ({:03}°, {} ft{}): {}{}{} | {} {:.0} KT, CROSS {:.0} KT {}{}",
+                runway.id,
+                runway.heading,
+                runway.length_ft,
+                ils,
+                status,
+                occupied,
+                finals,
+                along,
+                wind.headwind.abs(),
+                wind.crosswind.abs(),
+                side,
+                mins
+            );
+        }
+
+        if !self.airport.fixes.is_empty() {
+            let fixes: Vec<String> = self
+                .airport
+                .fixes
+                .iter()
+                .map(|fix| match &fix.hold {
+                    Some(hold) => format!(
+                        "{} ({:03}°/{:.0} NM, hold {:03}° {:?} {}-{} ft)",
+                        fix.name,
+                        fix.bearing,
+                        fix.range_nm,
+                        hold.inbound_course,
+                        hold.turn,
+                        hold.altitude_ft(0),
+                        hold.altitude_ft(hold.levels - 1)
+                    ),
+                    None => format!("{} ({:03}°/{:.0} NM)", fix.name, fix.bearing, fix.range_nm),
+                })
+                .collect();
+            println!("\n--- FIXES ---");
+            println!("  {}", fixes.join(" | "));
+        }
+
+        let mut due: Vec<&Inbound> = self
+            .inbound
+            .iter()
+            .filter(|i| i.entry_tick <= self.tick + INBOUND_NOTICE_TICKS)
+            .collect();
+        if !due.is_empty() {
+            due.sort_by_key(|i| i.entry_tick);
+            println!("\n--- INBOUND ---");
+            for inbound in due {
+                let bearing = inbound.plane.flight.as_ref().map_or(0.0, FlightState::bearing_from_field);
+                println!(
+                    "  {} | enters from {:03}° at tick {} (+{})",
+                    inbound.plane.flight_plan.summary(),
+                    bearing.round() as u16 % 360,
+                    inbound.entry_tick,
+                    inbound.entry_tick - self.tick
+                );
+            }
+        }
+
+        let estimates = self.sequence.estimates(&self.planes, self.tick);
+        if !estimates.is_empty() {
+            println!("\n--- ARRIVAL SEQUENCE ---");
+            for (number, estimate) in (1..).zip(&estimates) {
+                let status = self
+                    .planes
+                    .iter()
+                    .find(|p| p.id == estimate.plane)
+                    .map(|p| format!("{:?}", p.status))
+                    .unwrap_or_default();
+                println!(
+                    "  {}. {} {} | EST tick {} (+{})",
+                    number,
+                    estimate.plane,
+                    status,
+                    estimate.tick,
+                    estimate.tick - self.tick
+                );
+            }
         }
 
+        if let Some(scheduler) = &self.scheduler {
+            let schedule = &scheduler.schedule;
+            println!("\n--- SCHEDULE (AMAN/DMAN, first-fit heuristic) ---");
+            for slot in &schedule.slots {
+                println!("  {}", slot);
+            }
+            for plane in &schedule.unplaced {
+                println!("  {}: no usable runway", plane);
+            }
+            println!(
+                "  Predicted delay: {} ticks over {} movements",
+                schedule.total_delay(),
+                schedule.slots.len()
+            );
+            let report = scheduler.report;
+            println!(
+                "  Flown so far: {} movements, {} ticks of delay (planned {})",
+                report.movements, report.actual_ticks, report.planned_ticks
+            );
+            let advisories: Vec<String> = schedule
+                .advisories(&self.planes, self.tick)
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+            if !advisories.is_empty() {
+                println!("  Advise now: {}", advisories.join(" | "));
+            }
+        }
+
+        let ground = &self.airport.ground;
+        let holding_points: Vec<String> = ground
+            .nodes
+            .iter()
+            .filter_map(|n| Some(format!("{} ({})", n.id, n.runway.as_ref()?)))
+            .collect();
+        let crossings: Vec<String> = ground
+            .nodes
+            .iter()
+            .filter_map(|n| Some(format!("{} ({})", n.id, n.on_runway.as_ref()?)))
+            .collect();
+        println!("\n--- GROUND ---");
+        println!("  Taxiways: {} | Holding points: {}", ground.taxiway_names().join(" "), holding_points.join(" "));
+        if !crossings.is_empty() {
+            println!("  Runway crossings: {}", crossings.join(" "));
+        }
+        let gates: Vec<String> = self
+            .airport
+            .gates
+            .iter()
+            .map(|g| format!("{} ({}) {}", g.id, g.size, g.occupant.as_deref().unwrap_or("free")))
+            .collect();
+        println!("  Gates: {}", gates.join(" | "));
+
+        // Emergencies first, then minimum fuel, then everyone else
+        let mut planes: Vec<&Plane> = self.planes.iter().collect();
+        planes.sort_by_key(|p| std::cmp::Reverse((p.has_priority(), p.fuel_state)));
         println!("\n--- AIRCRAFT ---");
-        for plane in &self.planes {
-            let timer_info = if plane.timer > 0 { format!(" ({} ticks left)", plane.timer) } else { "".to_string() };
+        for plane in planes {
+            let timer_info = match (&plane.flight, &plane.ground) {
+                (Some(flight), _) => format!(" ({})", position_report(flight)),
+                (None, Some(position)) if plane.timer > 0 => {
+                    format!(" ({}, {} ticks left)", taxi_report(position, ground), plane.timer)
+                }
+                (None, None) if plane.timer > 0 => format!(" ({} ticks left)", plane.timer),
+                (None, Some(position)) => format!(" ({})", taxi_report(position, ground)),
+                (None, None) => "".to_string(),
+            };
+            let fuel = match plane.fuel_state {
+                FuelState::Mayday => "[MAYDAY FUEL] ",
+                FuelState::Minimum => "[MIN FUEL] ",
+                FuelState::Normal => "",
+            };
+            let fuel = match &plane.emergency {
+                Some(emergency) => format!("[{}] {}", emergency.kind.to_string().to_uppercase(), fuel),
+                None => fuel.to_string(),
+            };
+            let endurance = if plane.flight.is_some() {
+                format!(" | FUEL {:.0} MIN", plane.endurance_minutes())
+            } else {
+                "".to_string()
+            };
             println!(
-                "  - {}: {:?}{} | {}",
+                "  - {}{}: {:?}{}{} | {}",
+                fuel,
                 plane.id,
                 plane.status,
                 timer_info,
+                endurance,
                 plane.flight_plan.summary()
             );
         }
         
         println!("\n--- AWAITING YOUR COMMAND ---");
-        println!("Commands: pushback [id] | taxi [id] | takeoff [id] [runway] | land [id] [runway] | wait | quit");
+        println!("Commands: pushback [id] | taxi [id] [runway/gate] via [twy...] | cross [id] [runway] | gate [id] [gate] | lineup [id] [runway] | takeoff [id] [runway] | land [id] [runway] | goaround [id] | alert [id] | wait | quit");
+        println!("Vectors:  heading [id] [hdg] | climb/descend [id] [alt] | speed [id] [kt] | direct [id] [fix] | hold [id] [fix]");
+        println!("Arrivals: sequence [id] after [id]");
     }
 
-    /// Handle user input
+    /// Handle user input. Lines that don't parse are reported without a
+    /// penalty; only valid commands that are operationally wrong cost strikes.
     pub fn process_command(&mut self, command: String) {
         if self.game_over {
             return;
         }
-        let parts: Vec<&str> = command.trim().split_whitespace().collect();
-        if parts.is_empty() {
-            return;
+        if !command.trim().is_empty() {
+            self.history.push(RecordedInput::Command {
+                tick: self.tick,
+                command: command.clone(),
+            });
         }
+        match Command::parse(&command) {
+            Ok(command) => self.execute(command),
+            Err(ParseError::Empty) => {}
+            Err(error) => self.events.push(SimEvent::CommandRejected(error)),
+        }
+    }
 
-        let command = parts[0];
-        let plane_id = parts.get(1).map(|s| s.to_uppercase());
-        let runway_id = parts.get(2).map(|s| s.to_uppercase());
-
-        // Find the plane
-        let plane_opt = self.planes.iter_mut().find(|p| p.id.eq_ignore_ascii_case(plane_id.as_deref().unwrap_or_default()));
-
-
-        if plane_opt.is_none() && command != "quit" && command != "wait" {
-             // Adding "wait" command to allow game to tick without action
-            println!("COMMAND ERROR: Plane not found: {:?}", plane_id);
-            if !parts.is_empty() && command != "" {
-                 self.add_penalty();
-            }
+    /// Carry out an already-parsed command
+    pub fn execute(&mut self, command: Command) {
+        if self.game_over {
             return;
         }
-
-
         match command {
-            "pushback" => {
-                let plane = plane_opt.unwrap(); // Safe now
+            Command::Pushback { plane } => {
+                let Some(plane) = find_plane(&mut self.planes, &plane) else {
+                    return self.add_penalty(PenaltyReason::UnknownPlane(plane));
+                };
                 if plane.status == PlaneStatus::ReadyForPushback {
-                    println!("ATC: {} cleared for pushback.", plane.id);
-                    plane.status = PlaneStatus::TaxiingToRunway;
-                    plane.timer = 15; // 15 ticks to taxi
-                    self.add_score(5); // Half points
+                    // Off the gate and onto the ramp, waiting for taxi
+                    plane.status = PlaneStatus::ReadyToTaxi;
+                    if let Some(spot) = plane.gate.take().and_then(|g| self.airport.ground.pushback_spot(&g)) {
+                        plane.ground = Some(GroundPosition::at(spot));
+                    }
+                    self.airport.release_gate(&plane.id);
+                    let plane = plane.id.clone();
+                    self.stats.pushbacks += 1;
+                    self.clear(plane, Clearance::Pushback, 5); // Half points
                 } else if plane.status == PlaneStatus::Boarding {
-                    println!("PENALTY: {} is still boarding! Can't pushback!", plane.id);
-                    self.add_penalty();
+                    let reason = PenaltyReason::StillBoarding(plane.id.clone());
+                    self.add_penalty(reason);
                 } else {
-                    println!("PENALTY: {} is not ready for pushback.", plane.id);
-                    self.add_penalty();
+                    let reason = PenaltyReason::NotReadyForPushback(plane.id.clone());
+                    self.add_penalty(reason);
                 }
             }
-            "takeoff" => {
-                let plane = plane_opt.unwrap(); // Safe now
-                let rwy_id = match runway_id {
-                    Some(id) => id,
-                    None => {
-                        println!("PENALTY: Must specify runway for takeoff.");
-                        self.add_penalty();
-                        return;
-                    }
+            Command::Lineup { plane, runway } => {
+                let Some(plane) = find_plane(&mut self.planes, &plane) else {
+                    return self.add_penalty(PenaltyReason::UnknownPlane(plane));
                 };
-                
                 if plane.status != PlaneStatus::ReadyForTakeoff {
-                    println!("PENALTY: {} is not ready for takeoff.", plane.id);
-                    self.add_penalty();
+                    let reason = PenaltyReason::NotReadyForTakeoff(plane.id.clone());
+                    self.add_penalty(reason);
                     return;
                 }
 
-                match self.airport.get_runway(&rwy_id) {
-                    Some(runway) if runway.is_open => {
-                        println!("ATC: {} cleared for takeoff, runway {}.", plane.id, runway.id);
-                        plane.status = PlaneStatus::InAir;
-                        plane.timer = 30; // 30 ticks until next event (e.g., ready to land)
-                        self.add_score(10); // Full points
-                    }
-                    Some(runway) => { // Runway exists but is not open
-                         println!("PENALTY: Runway {} is CLOSED! You can't clear {} for takeoff!", runway.id, plane.id);
-                         self.add_penalty();
-                    }
-                    None => { // Runway doesn't exist
-                        println!("PENALTY: Runway {} does not exist!", rwy_id);
-                        self.add_penalty();
+                if let Err(reason) = check_holding_short(&self.airport, plane, &runway) {
+                    return self.add_penalty(reason);
+                }
+
+                let operation = RunwayOperation::Takeoff;
+                match check_runway_free(&self.airport, plane, &runway, operation, self.tick) {
+                    Ok(runway) => {
+                        let runway = runway.id.clone();
+                        if let Some(r) = self.airport.get_runway_mut(&runway) {
+                            r.line_up(&plane.id);
+                        }
+                        plane.status = PlaneStatus::LinedUpAndWaiting;
+                        let plane = plane.id.clone();
+                        // No points: the takeoff that follows earns them
+                        self.events.push(SimEvent::Clearance {
+                            plane,
+                            clearance: Clearance::LineUp { runway },
+                        });
                     }
+                    Err(reason) => self.add_penalty(reason),
                 }
             }
-            "land" => {
-                let plane = plane_opt.unwrap(); // Safe now
-                let rwy_id = match runway_id {
-                    Some(id) => id,
-                    None => {
-                        println!("PENALTY: Must specify runway for landing.");
-                        self.add_penalty();
-                        return;
+            Command::Takeoff { plane, runway } => {
+                let Some(plane) = find_plane(&mut self.planes, &plane) else {
+                    return self.add_penalty(PenaltyReason::UnknownPlane(plane));
+                };
+                if !matches!(plane.status, PlaneStatus::ReadyForTakeoff | PlaneStatus::LinedUpAndWaiting) {
+                    let reason = PenaltyReason::NotReadyForTakeoff(plane.id.clone());
+                    self.add_penalty(reason);
+                    return;
+                }
+                if let Err(reason) = check_holding_short(&self.airport, plane, &runway) {
+                    return self.add_penalty(reason);
+                }
+
+                let operation = RunwayOperation::Takeoff;
+                match check_runway(&self.airport, plane, &runway, operation, self.tick) {
+                    Ok(runway) => {
+                        let (x_nm, y_nm) = liftoff_point(&self.airport, &runway);
+                        let heading = record_movement(&mut self.airport, plane, &runway, operation, self.tick);
+                        if let Some(scheduler) = &mut self.scheduler {
+                            scheduler.record_movement(&plane.id, operation, self.tick);
+                        }
+                        plane.status = PlaneStatus::Departing;
+                        plane.timer = 0;
+                        plane.ground = None;
+                        let departure = FlightState::departing(x_nm, y_nm, f64::from(heading), &plane.performance());
+                        plane.flight = Some(departure);
+                        let plane = plane.id.clone();
+                        self.stats.takeoffs += 1;
+                        self.clear(plane, Clearance::Takeoff { runway }, 10); // Full points
                     }
+                    Err(reason) => self.add_penalty(reason),
+                }
+            }
+            Command::Land { plane, runway } => {
+                // Emergencies waiting to land go first
+                let waiting = self
+                    .planes
+                    .iter()
+                    .find(|p| p.status == PlaneStatus::ReadyToLand && p.has_priority())
+                    .map(|p| p.id.clone());
+                // As do planes ahead in the sequence that are ready
+                let ready_ahead = self.sequence.position(&plane).and_then(|at| {
+                    self.sequence.order()[..at]
+                        .iter()
+                        .find(|id| self.planes.iter().any(|p| &p.id == *id && p.status == PlaneStatus::ReadyToLand))
+                        .cloned()
+                });
+                let Some(plane) = find_plane(&mut self.planes, &plane) else {
+                    return self.add_penalty(PenaltyReason::UnknownPlane(plane));
                 };
-                
                 if plane.status != PlaneStatus::ReadyToLand {
-                    println!("PENALTY: {} is not ready to land.", plane.id);
-                    self.add_penalty();
+                    let reason = PenaltyReason::NotReadyToLand(plane.id.clone());
+                    self.add_penalty(reason);
                     return;
                 }
+                if !plane.has_priority()
+                    && let Some(priority) = waiting
+                {
+                    let reason = PenaltyReason::NotPrioritised {
+                        plane: plane.id.clone(),
+                        priority,
+                    };
+                    return self.add_penalty(reason);
+                }
+                if let Some(ahead) = ready_ahead {
+                    let reason = PenaltyReason::OutOfSequence {
+                        plane: plane.id.clone(),
+                        ahead,
+                    };
+                    return self.add_penalty(reason);
+                }
 
-                match self.airport.get_runway(&rwy_id) {
-                    Some(runway) if runway.is_open => {
-                        println!("ATC: {} cleared to land, runway {}.", plane.id, runway.id);
-                        plane.status = PlaneStatus::TaxiingToGate;
-                        plane.timer = 10; // 10 ticks to taxi to gate
-                        self.add_score(10); // Full points
-                    }
-                    Some(runway) => {
-                         println!("PENALTY: Runway {} is CLOSED! You can't clear {} to land!", runway.id, plane.id);
-                         self.add_penalty();
-                    }
-                    None => {
-                        println!("PENALTY: Runway {} does not exist!", rwy_id);
-                        self.add_penalty();
+                let operation = RunwayOperation::Landing;
+                match check_runway(&self.airport, plane, &runway, operation, self.tick) {
+                    Ok(runway) => {
+                        // Points come at touchdown, if the runway is still free
+                        let heading = self.airport.get_runway(&runway).map_or(360, |r| r.heading);
+                        if let Some(flight) = plane.flight.as_mut() {
+                            flight.fly_final(f64::from(heading), f64::from(plane.aircraft.approach_speed_kt));
+                        }
+                        plane.status = PlaneStatus::OnFinal;
+                        plane.timer = flight::FINAL_APPROACH_TICKS;
+                        plane.runway = Some(runway.clone());
+                        if let Some(r) = self.airport.get_runway_mut(&runway) {
+                            r.on_final.get_or_insert_with(|| plane.id.clone());
+                        }
+                        self.events.push(SimEvent::Clearance {
+                            plane: plane.id.clone(),
+                            clearance: Clearance::Land { runway },
+                        });
                     }
+                    Err(reason) => self.add_penalty(reason),
                 }
             }
-            "taxi" => {
-                // In this simple version, "taxi" is handled by pushback.
-                // You could expand this to require a "taxi" command.
-                println!("INFO: 'pushback' command handles taxiing to runway.");
+            Command::GoAround { plane } => {
+                let Some(plane) = find_plane(&mut self.planes, &plane) else {
+                    return self.add_penalty(PenaltyReason::UnknownPlane(plane));
+                };
+                let Some(runway) = plane.runway.clone().filter(|_| plane.status == PlaneStatus::OnFinal) else {
+                    let reason = PenaltyReason::NotOnFinal(plane.id.clone());
+                    return self.add_penalty(reason);
+                };
+                // A go-around is needed if the landing couldn't be cleared now
+                let needed = check_runway(&self.airport, plane, &runway, RunwayOperation::Landing, self.tick).is_err();
+                let id = plane.id.clone();
+                self.go_around(&id, GoAroundReason::Instructed);
+                if !needed {
+                    self.add_penalty(PenaltyReason::UnnecessaryGoAround { plane: id, runway });
+                }
             }
-            "wait" => {
+            Command::Heading { plane, heading } => {
+                self.vector(&plane, |p| p.assign_heading(heading));
+            }
+            Command::Altitude { plane, altitude_ft } => {
+                self.vector(&plane, |p| p.assign_altitude(altitude_ft));
+            }
+            Command::Speed { plane, speed_kt } => {
+                self.vector(&plane, |p| p.assign_speed(speed_kt));
+            }
+            Command::Direct { plane, fix } => {
+                let Some(fix) = self.airport.get_fix(&fix).cloned() else {
+                    return self.add_penalty(PenaltyReason::UnknownFix(fix));
+                };
+                self.vector(&plane, |p| p.proceed_direct(&fix));
+            }
+            Command::Taxi {
+                plane,
+                destination,
+                via,
+            } => self.taxi(&plane, &destination, via),
+            Command::Cross { plane, runway } => self.cross(&plane, &runway),
+            Command::Alert { plane } => self.alert(&plane),
+            Command::Hold { plane, fix } => self.hold(&plane, &fix),
+            Command::Sequence { plane, after } => self.resequence(&plane, &after),
+            Command::Gate { plane, gate } => self.assign_gate(&plane, &gate),
+            Command::Wait => {
                 // Do nothing, just let the game tick
-                println!("ATC: Holding position.");
+                self.events.push(SimEvent::Holding);
             }
-            "quit" => {
+            Command::Quit => {
                 self.game_over = true;
-                println!("Thanks for playing!");
+                self.events.push(SimEvent::SessionEnded);
             }
+        }
+    }
+
+    /// Route a plane over the taxiway graph: departures to a runway's
+    /// holding point, arrivals to a gate. Like vectors, taxi clearances earn
+    /// no points.
+    fn taxi(&mut self, id: &str, destination: &str, via: Vec<String>) {
+        let Some(plane) = find_plane(&mut self.planes, id) else {
+            return self.add_penalty(PenaltyReason::UnknownPlane(id.to_string()));
+        };
+        let departing = match plane.status {
+            PlaneStatus::ReadyToTaxi | PlaneStatus::TaxiingToRunway | PlaneStatus::ReadyForTakeoff => true,
+            PlaneStatus::TaxiingToGate | PlaneStatus::HoldingForGate => false,
             _ => {
-                if !command.is_empty() {
-                    println!("Unknown command: {}", command);
-                }
+                let reason = PenaltyReason::NotReadyToTaxi(plane.id.clone());
+                return self.add_penalty(reason);
             }
+        };
+        let size = plane.gate_size();
+        let Some(position) = plane.ground.as_mut() else {
+            let reason = PenaltyReason::NotReadyToTaxi(plane.id.clone());
+            return self.add_penalty(reason);
+        };
+
+        let layout = &self.airport.ground;
+        let (target, targets): (TaxiTarget, Vec<&str>) = if let Some(runway) = self.airport.get_runway(destination) {
+            let holding_points = layout.holding_points(&runway.id).map(|n| n.id.as_str()).collect();
+            (TaxiTarget::Runway(runway.id.clone()), holding_points)
+        } else if let Some(gate) = self.airport.gates.iter().find(|g| g.id.eq_ignore_ascii_case(destination)) {
+            (TaxiTarget::Gate(gate.id.clone()), vec![gate.id.as_str()])
+        } else if destination.starts_with(|c: char| c.is_ascii_digit()) {
+            return self.add_penalty(PenaltyReason::UnknownRunway(destination.to_string()));
+        } else {
+            return self.add_penalty(PenaltyReason::UnknownGate(destination.to_string()));
+        };
+        if departing != matches!(target, TaxiTarget::Runway(_)) {
+            let reason = PenaltyReason::WrongTaxiDestination {
+                plane: plane.id.clone(),
+                target,
+            };
+            return self.add_penalty(reason);
+        }
+        if let TaxiTarget::Gate(gate) = &target
+            && let Err(reason) = check_gate(&self.airport, &plane.id, size, gate)
+        {
+            return self.add_penalty(reason);
         }
+
+        let Some(route) = layout.route(position.route_origin(), &targets, &via) else {
+            let reason = PenaltyReason::NoTaxiRoute {
+                plane: plane.id.clone(),
+                target,
+                via,
+            };
+            return self.add_penalty(reason);
+        };
+        position.follow(route);
+        if let TaxiTarget::Gate(gate) = &target {
+            self.airport.assign_gate(&plane.id, gate);
+            plane.gate = Some(gate.clone());
+        }
+        plane.status = if departing {
+            PlaneStatus::TaxiingToRunway
+        } else {
+            PlaneStatus::TaxiingToGate
+        };
+        self.events.push(SimEvent::Clearance {
+            plane: plane.id.clone(),
+            clearance: Clearance::Taxi { target, via },
+        });
+    }
+
+    /// Give an arrival its gate. On the ground it taxis straight there;
+    /// in the air it heads for it after landing.
+    fn assign_gate(&mut self, id: &str, gate: &str) {
+        let Some(plane) = find_plane(&mut self.planes, id) else {
+            return self.add_penalty(PenaltyReason::UnknownPlane(id.to_string()));
+        };
+        if !plane.is_arrival() {
+            let reason = PenaltyReason::NotArriving(plane.id.clone());
+            return self.add_penalty(reason);
+        }
+        let Some(gate) = self.airport.get_gate(gate).map(|g| g.id.clone()) else {
+            return self.add_penalty(PenaltyReason::UnknownGate(gate.to_string()));
+        };
+        if let Err(reason) = check_gate(&self.airport, &plane.id, plane.gate_size(), &gate) {
+            return self.add_penalty(reason);
+        }
+
+        if let Some(position) = plane.ground.as_mut() {
+            let Some(route) = self.airport.ground.route(position.route_origin(), &[&gate], &[]) else {
+                let reason = PenaltyReason::NoTaxiRoute {
+                    plane: plane.id.clone(),
+                    target: TaxiTarget::Gate(gate),
+                    via: Vec::new(),
+                };
+                return self.add_penalty(reason);
+            };
+            position.follow(route);
+            plane.status = PlaneStatus::TaxiingToGate;
+        }
+        self.airport.assign_gate(&plane.id, &gate);
+        plane.gate = Some(gate.clone());
+        self.events.push(SimEvent::Clearance {
+            plane: plane.id.clone(),
+            clearance: Clearance::Gate { gate },
+        });
+    }
+
+    /// Clear a taxiing plane across a runway on its route. Crossing a runway
+    /// that is in use is a runway incursion, not a strike: the plane goes.
+    fn cross(&mut self, id: &str, runway: &str) {
+        let Some(plane) = find_plane(&mut self.planes, id) else {
+            return self.add_penalty(PenaltyReason::UnknownPlane(id.to_string()));
+        };
+        let Some(runway) = self.airport.get_runway(runway) else {
+            return self.add_penalty(PenaltyReason::UnknownRunway(runway.to_string()));
+        };
+        let layout = &self.airport.ground;
+        let Some(position) = plane
+            .ground
+            .as_mut()
+            .filter(|g| g.crossings_ahead(layout).iter().any(|r| r.eq_ignore_ascii_case(&runway.id)))
+        else {
+            let reason = PenaltyReason::NoCrossing {
+                plane: plane.id.clone(),
+                runway: runway.id.clone(),
+            };
+            return self.add_penalty(reason);
+        };
+
+        position.clear_to_cross(&runway.id);
+        let severity = runway.incursion_severity(self.tick);
+        let (plane, runway) = (plane.id.clone(), runway.id.clone());
+        if let Some(r) = self.airport.get_runway_mut(&runway) {
+            r.crossing.get_or_insert_with(|| plane.clone());
+        }
+        self.events.push(SimEvent::Clearance {
+            plane: plane.clone(),
+            clearance: Clearance::Cross { runway: runway.clone() },
+        });
+        if severity != IncursionSeverity::D {
+            self.record_incursion(Incursion {
+                plane,
+                runway,
+                severity,
+                cause: IncursionCause::CrossingClearance,
+            });
+        }
+    }
+
+    /// Give an airborne plane an instruction. Vectors earn no points; they
+    /// are how the controller sets up the points-scoring clearances. A plane
+    /// on final is flying the approach and takes no vectors.
+    fn vector(
+        &mut self,
+        id: &str,
+        instruct: impl FnOnce(&mut Plane) -> Result<Clearance, PenaltyReason>,
+    ) {
+        let Some(plane) = find_plane(&mut self.planes, id) else {
+            return self.add_penalty(PenaltyReason::UnknownPlane(id.to_string()));
+        };
+        if plane.flight.is_none() {
+            let reason = PenaltyReason::NotAirborne(plane.id.clone());
+            return self.add_penalty(reason);
+        }
+        if plane.status == PlaneStatus::OnFinal {
+            let reason = PenaltyReason::OnFinal(plane.id.clone());
+            return self.add_penalty(reason);
+        }
+        match instruct(plane) {
+            Ok(clearance) => {
+                let plane = plane.id.clone();
+                self.events.push(SimEvent::Clearance { plane, clearance });
+            }
+            Err(reason) => self.add_penalty(reason),
+        }
+    }
+
+    /// Send an arrival to the published hold at `fix`, at the lowest free
+    /// level of the stack
+    fn hold(&mut self, id: &str, fix: &str) {
+        let Some(fix) = self.airport.get_fix(fix).cloned() else {
+            return self.add_penalty(PenaltyReason::UnknownFix(fix.to_string()));
+        };
+        let Some(hold) = fix.hold.clone() else {
+            return self.add_penalty(PenaltyReason::NoPublishedHold(fix.name));
+        };
+        let taken: Vec<u32> = self
+            .planes
+            .iter()
+            .filter(|p| !p.id.eq_ignore_ascii_case(id))
+            .filter_map(|p| p.flight.as_ref()?.holding.as_ref())
+            .filter(|h| h.fix == fix.name)
+            .map(|h| h.level)
+            .collect();
+        let Some(level) = (0..hold.levels).find(|l| !taken.contains(l)) else {
+            return self.add_penalty(PenaltyReason::HoldFull(fix.name));
+        };
+        let mut changes = Vec::new();
+        self.vector(id, |p| p.hold(&fix, &hold, level, &mut changes));
+        self.events.extend(changes);
+    }
+
+    /// Move an arrival to land right after another. Emergencies can't be
+    /// put behind normal traffic.
+    fn resequence(&mut self, id: &str, after: &str) {
+        let mut sequence = self.sequence.clone();
+        let Some(number) = sequence.move_after(id, after) else {
+            let missing = if self.sequence.position(id).is_none() { id } else { after };
+            return self.add_penalty(PenaltyReason::NotInSequence(missing.to_string()));
+        };
+        if let Some((plane, priority)) = sequence.priority_violation(&self.planes) {
+            return self.add_penalty(PenaltyReason::NotPrioritised { plane, priority });
+        }
+        self.sequence = sequence;
+        self.events.push(SimEvent::Clearance {
+            plane: id.to_string(),
+            clearance: Clearance::Sequence {
+                after: after.to_string(),
+                number,
+            },
+        });
+    }
+
+    /// Call out the services an emergency needs. Worth points the first
+    /// time; nothing to call out is a strike.
+    fn alert(&mut self, id: &str) {
+        let Some(plane) = find_plane(&mut self.planes, id) else {
+            return self.add_penalty(PenaltyReason::UnknownPlane(id.to_string()));
+        };
+        let needs = plane.emergency.as_mut().and_then(|e| Some((e.kind.services()?, e)));
+        let Some((services, emergency)) = needs else {
+            let reason = PenaltyReason::NoEmergency(plane.id.clone());
+            return self.add_penalty(reason);
+        };
+        let points = if emergency.services_alerted { 0 } else { 5 };
+        emergency.services_alerted = true;
+        let plane = plane.id.clone();
+        self.clear(plane, Clearance::Alert { services }, points);
+    }
+
+    /// Record a successful clearance and award its points
+    fn clear(&mut self, plane: String, clearance: Clearance, points: i32) {
+        self.events.push(SimEvent::Clearance { plane, clearance });
+        self.add_score(points);
     }
 
     fn add_score(&mut self, amount: i32) {
         self.score += amount;
         // Reset penalty strikes on a successful command
         self.penalty_strikes = 0;
-        println!("Score: {}", self.score);
+        self.events.push(SimEvent::ScoreChanged {
+            delta: amount,
+            score: self.score,
+        });
     }
 
-    fn add_penalty(&mut self) {
+    fn add_penalty(&mut self, reason: PenaltyReason) {
         self.score -= 5;
         self.penalty_strikes += 1;
-        println!("PENALTY! Score: {}, Strikes: {}/3", self.score, self.penalty_strikes);
+        self.stats.penalties += 1;
+        self.events.push(SimEvent::Penalty {
+            reason,
+            strikes: self.penalty_strikes,
+        });
+        self.events.push(SimEvent::ScoreChanged {
+            delta: -5,
+            score: self.score,
+        });
 
         if self.penalty_strikes >= 3 {
-            println!("\n******************************************");
-            println!("Alright, get out, you're fired.");
-            println!("FINAL SCORE: {}", self.score);
-            println!("******************************************");
-            self.game_over = true;
+            self.end_game(GameOverReason::Fired);
         }
     }
 
+    /// Incidents cost more than a strike, and are never forgiven
+    fn record_incident(&mut self, incident: Incident) {
+        self.incidents += 1;
+        self.score -= 10;
+        self.events.push(SimEvent::Incident {
+            incident,
+            total: self.incidents,
+        });
+        self.events.push(SimEvent::ScoreChanged {
+            delta: -10,
+            score: self.score,
+        });
+    }
+
+    fn record_incursion(&mut self, incursion: Incursion) {
+        self.incursions.push(incursion.clone());
+        self.record_incident(Incident::RunwayIncursion(incursion));
+    }
+
+    fn end_game(&mut self, reason: GameOverReason) {
+        self.events.push(SimEvent::GameOver {
+            final_score: self.score,
+            reason,
+        });
+        self.game_over = true;
+    }
+
     pub fn is_game_over(&self) -> bool {
         self.game_over
     }
 }
+
+/// Range and bearing from the field, altitude with a climb/descent marker,
+/// heading and speed, and the hold it is in, e.g.
+/// "12 NM 270° | 4500 ft ↓ | HDG 090 | 230 KT"
+fn position_report(flight: &FlightState) -> String {
+    let trend = if flight.vertical_speed_fpm > 100.0 {
+        " ↑"
+    } else if flight.vertical_speed_fpm < -100.0 {
+        " ↓"
+    } else {
+        ""
+    };
+    let hold = flight.holding.as_ref().map_or("".to_string(), |h| format!(" | HOLD {}", h.fix));
+    format!(
+        "{:.0} NM {:03.0}° | {:.0} ft{} | HDG {:03.0} | {:.0} KT{}",
+        flight.range_nm(),
+        flight.bearing_from_field(),
+        flight.altitude_ft,
+        trend,
+        flight.heading,
+        flight.ground_speed_kt,
+        hold
+    )
+}
+
+/// Where a plane is on the surface, e.g. "A-RA -> A-D on A, to HS-24L"
+fn taxi_report(position: &GroundPosition, layout: &GroundLayout) -> String {
+    if let Some(runway) = &position.holding_for {
+        return format!("holding short of runway {} at {}", runway, position.at);
+    }
+    match (position.route.front(), position.route.back()) {
+        (Some(next), Some(last)) => {
+            let taxiway = layout.taxiway_between(&position.at, next).map_or("", |t| t.name.as_str());
+            format!("{} -> {} on {}, to {}", position.at, next, taxiway, last)
+        }
+        _ => format!("at {}", position.at),
+    }
+}
+
+/// Put a departure at a free gate it fits at to board. Returns false if
+/// there is none.
+fn park_at_gate(plane: &mut Plane, airport: &mut Airport, rng: &mut impl Rng) -> bool {
+    let free: Vec<String> = airport.free_gates(plane.gate_size()).map(|g| g.id.clone()).collect();
+    if free.is_empty() {
+        return false;
+    }
+    let gate = &free[rng.gen_range(0..free.len())];
+    airport.assign_gate(&plane.id, gate);
+    plane.gate = Some(gate.clone());
+    plane.ground = Some(GroundPosition::at(gate));
+    true
+}
+
+/// The runway exit with the shortest taxi to any of `targets`, and that route
+fn arrival_route(layout: &GroundLayout, runway: &str, targets: &[&str]) -> Option<(String, TaxiRoute)> {
+    layout
+        .holding_points(runway)
+        .filter_map(|exit| Some((exit.id.clone(), layout.route(&exit.id, targets, &[])?)))
+        .min_by(|a, b| a.1.length_ft.total_cmp(&b.1.length_ft))
+}
+
+/// Check gate `id` can take `plane`: it is free (or already the plane's)
+/// and big enough for an aircraft of `size`
+fn check_gate(airport: &Airport, plane: &str, size: GateSize, id: &str) -> Result<(), PenaltyReason> {
+    let Some(gate) = airport.get_gate(id) else {
+        return Err(PenaltyReason::UnknownGate(id.to_string()));
+    };
+    if let Some(occupant) = &gate.occupant
+        && occupant != plane
+    {
+        return Err(PenaltyReason::GateOccupied {
+            plane: plane.to_string(),
+            gate: gate.id.clone(),
+            occupant: occupant.clone(),
+        });
+    }
+    if !gate.fits(size) {
+        return Err(PenaltyReason::GateTooSmall {
+            plane: plane.to_string(),
+            gate: gate.id.clone(),
+            size: gate.size,
+            needed: size,
+        });
+    }
+    Ok(())
+}
+
+/// A plane waiting at a runway may only use that runway
+fn check_holding_short(airport: &Airport, plane: &Plane, runway: &str) -> Result<(), PenaltyReason> {
+    match plane.holding_short(&airport.ground) {
+        Some(at) if !at.eq_ignore_ascii_case(runway) => Err(PenaltyReason::WrongRunway {
+            plane: plane.id.clone(),
+            at: at.to_string(),
+            runway: runway.to_string(),
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Note a takeoff or landing on the runway for occupancy and wake spacing.
+/// Returns the runway heading.
+fn record_movement(
+    airport: &mut Airport,
+    plane: &Plane,
+    id: &str,
+    operation: RunwayOperation,
+    tick: u64,
+) -> u16 {
+    let Some(runway) = airport.get_runway_mut(id) else {
+        return 360;
+    };
+    runway.record_movement(Movement {
+        plane: plane.id.clone(),
+        tick,
+        weight_class: plane.weight_class(),
+        operation,
+    });
+    runway.heading
+}
+
+/// Where a departure from runway `id` lifts off, in nautical miles from the
+/// field: halfway down the runway from its threshold
+fn liftoff_point(airport: &Airport, id: &str) -> (f64, f64) {
+    let Some(runway) = airport.get_runway(id) else {
+        return (0.0, 0.0);
+    };
+    let (x_ft, y_ft) = (runway.threshold_x_ft, runway.threshold_y_ft);
+    let roll_ft = f64::from(runway.length_ft) / 2.0;
+    let heading = f64::from(runway.heading).to_radians();
+    (
+        (x_ft + roll_ft * heading.sin()) / flight::FT_PER_NM,
+        (y_ft + roll_ft * heading.cos()) / flight::FT_PER_NM,
+    )
+}
+
+/// A plane on final for runway `id` has landed or gone around
+fn clear_final(airport: &mut Airport, plane: &str, id: &str) {
+    if let Some(runway) = airport.get_runway_mut(id)
+        && runway.on_final.as_deref() == Some(plane)
+    {
+        runway.on_final = None;
+    }
+}
+
+/// Look up a plane by callsign, ignoring case
+fn find_plane<'a>(planes: &'a mut [Plane], id: &str) -> Option<&'a mut Plane> {
+    planes.iter_mut().find(|p| p.id.eq_ignore_ascii_case(id))
+}
+
+/// Check that runway `id` exists, is open, and has nobody but `plane` on it
+fn check_runway_free<'a>(
+    airport: &'a Airport,
+    plane: &Plane,
+    id: &str,
+    operation: RunwayOperation,
+    tick: u64,
+) -> Result<&'a Runway, PenaltyReason> {
+    let Some(runway) = airport.get_runway(id) else {
+        return Err(PenaltyReason::UnknownRunway(id.to_string()));
+    };
+    if !runway.is_open {
+        return Err(PenaltyReason::RunwayClosed {
+            plane: plane.id.clone(),
+            runway: runway.id.clone(),
+            operation,
+        });
+    }
+    if let Some(occupant) = runway.occupant(tick)
+        && occupant != plane.id
+    {
+        return Err(PenaltyReason::RunwayOccupied {
+            plane: plane.id.clone(),
+            runway: runway.id.clone(),
+            occupant: occupant.to_string(),
+        });
+    }
+    Ok(runway)
+}
+
+/// Check that `plane` may use runway `id` at `tick`. Returns the runway's
+/// proper designator, or the penalty for clearing it anyway.
+fn check_runway(
+    airport: &Airport,
+    plane: &Plane,
+    id: &str,
+    operation: RunwayOperation,
+    tick: u64,
+) -> Result<String, PenaltyReason> {
+    let runway = check_runway_free(airport, plane, id, operation, tick)?;
+    if let Some(leader) = &runway.last_movement {
+        let clear_at = leader.tick + separation::wake_ticks(leader.weight_class, plane.weight_class());
+        if tick < clear_at {
+            return Err(PenaltyReason::WakeTurbulence {
+                plane: plane.id.clone(),
+                runway: runway.id.clone(),
+                leader: leader.plane.clone(),
+                leader_class: leader.weight_class,
+                wait_ticks: clear_at - tick,
+            });
+        }
+    }
+    plane.check_runway_suits(runway, &airport.weather, operation)?;
+    Ok(runway.id.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::airport::ClosureReason;
+    use crate::emergency::Services;
+
+    /// The command a controller working every plane by the book would give
+    /// it now, if any
+    fn routine_command(plane: &Plane) -> Option<String> {
+        let id = &plane.id;
+        match plane.status {
+            PlaneStatus::ReadyForPushback => Some(format!("pushback {}", id)),
+            PlaneStatus::ReadyToTaxi => Some(format!("taxi {} 24L", id)),
+            PlaneStatus::ReadyForTakeoff => Some(format!("takeoff {} 24L", id)),
+            PlaneStatus::ReadyToLand => Some(format!("land {} 24R", id)),
+            _ => None,
+        }
+    }
+
+    /// Play `ticks` ticks from `seed`, giving every routine command as soon
+    /// as it can be given, and return every event with its tick
+    fn routine_session(seed: u64, ticks: u64) -> Vec<(u64, SimEvent)> {
+        let mut game = Game::with_seed(seed);
+        let mut events = Vec::new();
+        while game.tick() < ticks && !game.is_game_over() {
+            let commands: Vec<String> = game.planes.iter().filter_map(routine_command).collect();
+            for command in commands {
+                game.process_command(command);
+            }
+            game.update();
+            events.extend(game.drain_events().into_iter().map(|e| (game.tick(), e)));
+        }
+        events
+    }
+
+    /// A session at the classic layout with no traffic, in calm, clear
+    /// weather that holds for the next hour
+    fn quiet_game(seed: u64) -> Game {
+        let mut game = Game::with_seed(seed);
+        game.planes.clear();
+        game.inbound.clear();
+        game.sequence = ArrivalSequence::new();
+        game.set_metar("KPDX 181853Z 00000KT 10SM CLR").unwrap();
+        game.drain_events();
+        game
+    }
+
+    /// A new flight of `aircraft_type`, in or out
+    fn new_plane(game: &mut Game, aircraft_type: &str, arriving: bool) -> Plane {
+        let plan = game.flight_plan_manager.generate_new_plan(
+            &[aircraft_type],
+            &game.navdata,
+            &game.airport.icao,
+            arriving,
+            &mut game.rng,
+        );
+        Plane::new(plan.unwrap(), &game.aircraft, &mut game.rng)
+    }
+
+    /// Put a departure of `aircraft_type` at ground node `at` in `status`
+    fn add_on_ground(game: &mut Game, aircraft_type: &str, at: &str, status: PlaneStatus) -> String {
+        let mut plane = new_plane(game, aircraft_type, false);
+        plane.status = status;
+        plane.ground = Some(GroundPosition::at(at));
+        let id = plane.id.clone();
+        game.planes.push(plane);
+        id
+    }
+
+    /// Put an E175 departure at runway `runway`'s holding point, ready for
+    /// takeoff
+    fn add_departure(game: &mut Game, runway: &str) -> String {
+        let holding_point = game.airport.ground.holding_points(runway).next().unwrap().id.clone();
+        add_on_ground(game, "E175", &holding_point, PlaneStatus::ReadyForTakeoff)
+    }
+
+    /// Put an arrival of `aircraft_type` 8 NM out on `bearing` at the
+    /// approach altitude, ready to land, at the back of the sequence
+    fn add_arrival(game: &mut Game, aircraft_type: &str, bearing: f64) -> String {
+        let mut plane = new_plane(game, aircraft_type, true);
+        plane.status = PlaneStatus::ReadyToLand;
+        plane.timer = 0;
+        plane.flight = Some(FlightState::arriving(bearing, 8.0, flight::APPROACH_ALTITUDE_FT, 180.0));
+        let id = plane.id.clone();
+        game.planes.push(plane);
+        game.sequence.update(&game.planes);
+        id
+    }
+
+    fn plane<'a>(game: &'a Game, id: &str) -> &'a Plane {
+        game.planes.iter().find(|p| p.id == id).unwrap()
+    }
+
+    /// Step with no commands until `found` picks out an event, for at most
+    /// `ticks` ticks
+    fn step_until<T>(game: &mut Game, ticks: u64, mut found: impl FnMut(&SimEvent) -> Option<T>) -> Option<T> {
+        for _ in 0..ticks {
+            if let Some(value) = step(game, &[]).iter().find_map(&mut found) {
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    fn penalties(events: &[SimEvent]) -> Vec<&PenaltyReason> {
+        events
+            .iter()
+            .filter_map(|e| match e {
+                SimEvent::Penalty { reason, .. } => Some(reason),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn incursions(events: &[SimEvent]) -> Vec<&Incursion> {
+        events
+            .iter()
+            .filter_map(|e| match e {
+                SimEvent::Incident {
+                    incident: Incident::RunwayIncursion(incursion),
+                    ..
+                } => Some(incursion),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn cleared(events: &[SimEvent], plane: &str) -> Vec<Clearance> {
+        events
+            .iter()
+            .filter_map(|e| match e {
+                SimEvent::Clearance { plane: p, clearance } if p == plane => Some(clearance.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Give every command, then run one tick, and return its events
+    fn step(game: &mut Game, commands: &[String]) -> Vec<SimEvent> {
+        for command in commands {
+            game.process_command(command.clone());
+        }
+        game.update();
+        game.drain_events()
+    }
+
+    fn collided(events: &[SimEvent]) -> bool {
+        events.iter().any(|e| {
+            matches!(
+                e,
+                SimEvent::GameOver {
+                    reason: GameOverReason::MidAirCollision { .. },
+                    ..
+                }
+            )
+        })
+    }
+
+    #[test]
+    fn departures_off_parallel_runways_do_not_collide() {
+        let mut game = quiet_game(7);
+        let left = add_departure(&mut game, "24L");
+        let right = add_departure(&mut game, "24R");
+        let events = step(&mut game, &[format!("takeoff {} 24L", left), format!("takeoff {} 24R", right)]);
+        assert!(!collided(&events), "{:?}", events);
+        assert!(!game.is_game_over());
+        assert_eq!(game.stats.takeoffs, 2);
+        // Side by side 1500 ft apart is still too close
+        assert!(events.iter().any(|e| matches!(
+            e,
+            SimEvent::Incident {
+                incident: Incident::LossOfSeparation { .. },
+                ..
+            }
+        )));
+    }
+
+    #[test]
+    fn departures_off_crossing_runways_collide() {
+        let mut game = quiet_game(7);
+        let left = add_departure(&mut game, "24L");
+        let crossing = add_departure(&mut game, "18");
+        let events = step(&mut game, &[format!("takeoff {} 24L", left), format!("takeoff {} 18", crossing)]);
+        assert!(collided(&events), "{:?}", events);
+        assert!(game.is_game_over());
+    }
+
+    #[test]
+    fn same_seed_and_commands_give_the_same_session() {
+        for seed in [1, 7, 42] {
+            let first = routine_session(seed, 120);
+            assert!(first.len() > 20, "seed {} produced only {} events", seed, first.len());
+            assert_eq!(first, routine_session(seed, 120), "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn different_seeds_give_different_sessions() {
+        assert_ne!(routine_session(1, 60), routine_session(2, 60));
+    }
+
+    /// An E175 on taxiway A at the foot of taxiway D, cleared to taxi to
+    /// runway 18 across 24L, and the events of that clearance
+    fn taxi_across_24l(game: &mut Game) -> String {
+        let id = add_on_ground(game, "E175", "A-D", PlaneStatus::ReadyToTaxi);
+        game.process_command(format!("taxi {} 18", id));
+        id
+    }
+
+    /// The incursion logged, if any, for clearing a plane across 24L after
+    /// `setup` has put traffic on it
+    fn crossing_incursion(setup: impl FnOnce(&mut Game) -> Vec<String>) -> Option<Incursion> {
+        let mut game = quiet_game(7);
+        let crossing = taxi_across_24l(&mut game);
+        let mut commands = setup(&mut game);
+        commands.push(format!("cross {} 24L", crossing));
+        let events = step(&mut game, &commands);
+        assert!(penalties(&events).is_empty(), "{:?}", events);
+        assert!(cleared(&events, &crossing).contains(&Clearance::Cross { runway: "24L".into() }));
+        incursions(&events).first().map(|i| (*i).clone())
+    }
+
+    #[test]
+    fn taxiing_plane_holds_short_until_cleared_across() {
+        let mut game = quiet_game(7);
+        let id = taxi_across_24l(&mut game);
+        let events = step(&mut game, &[]);
+        assert!(events.contains(&SimEvent::HoldingShort {
+            plane: id.clone(),
+            runway: "24L".into(),
+        }));
+        // It waits without asking again
+        for _ in 0..3 {
+            let events = step(&mut game, &[]);
+            assert!(!events.iter().any(|e| matches!(e, SimEvent::HoldingShort { .. })));
+        }
+        assert_eq!(plane(&game, &id).ground.as_ref().unwrap().at, "D-HS24L");
+
+        // Across a free runway: no incursion, and on to the holding point
+        let events = step(&mut game, &[format!("cross {} 24L", id)]);
+        assert!(incursions(&events).is_empty());
+        let ready = step_until(&mut game, 5, |e| match e {
+            SimEvent::PlaneStateChanged { plane, to, .. } if *plane == id => Some(*to),
+            _ => None,
+        });
+        assert_eq!(ready, Some(PlaneStatus::ReadyForTakeoff));
+        assert_eq!(plane(&game, &id).ground.as_ref().unwrap().at, "HS-18");
+    }
+
+    #[test]
+    fn crossing_a_free_runway_is_severity_d_and_not_logged() {
+        assert_eq!(crossing_incursion(|_| Vec::new()), None);
+    }
+
+    #[test]
+    fn crossing_in_front_of_a_lined_up_departure_is_severity_c() {
+        let incursion = crossing_incursion(|game| {
+            let departure = add_departure(game, "24L");
+            vec![format!("lineup {} 24L", departure)]
+        })
+        .unwrap();
+        assert_eq!(incursion.runway, "24L");
+        assert_eq!(incursion.severity, IncursionSeverity::C);
+        assert_eq!(incursion.cause, IncursionCause::CrossingClearance);
+    }
+
+    #[test]
+    fn crossing_behind_a_departure_rolling_is_severity_b() {
+        let incursion = crossing_incursion(|game| {
+            let departure = add_departure(game, "24L");
+            vec![format!("takeoff {} 24L", departure)]
+        })
+        .unwrap();
+        assert_eq!(incursion.severity, IncursionSeverity::B);
+    }
+
+    #[test]
+    fn crossing_in_front_of_an_arrival_is_severity_a() {
+        let incursion = crossing_incursion(|game| {
+            let arrival = add_arrival(game, "E175", 60.0);
+            vec![format!("land {} 24L", arrival)]
+        })
+        .unwrap();
+        assert_eq!(incursion.severity, IncursionSeverity::A);
+    }
+
+    #[test]
+    fn crossing_a_landing_roll_is_severity_a() {
+        let mut game = quiet_game(7);
+        let arrival = add_arrival(&mut game, "E175", 60.0);
+        step(&mut game, &[format!("land {} 24L", arrival)]);
+        let landed = step_until(&mut game, 5, |e| match e {
+            SimEvent::PlaneStateChanged { plane, to, .. } if *plane == arrival => Some(*to),
+            _ => None,
+        });
+        assert_eq!(landed, Some(PlaneStatus::TaxiingToGate));
+
+        let crossing = taxi_across_24l(&mut game);
+        let events = step(&mut game, &[format!("cross {} 24L", crossing)]);
+        let incursion = incursions(&events)[0];
+        assert_eq!(incursion.plane, crossing);
+        assert_eq!(incursion.severity, IncursionSeverity::A);
+    }
+
+    #[test]
+    fn crossing_a_runway_off_the_route_is_a_penalty() {
+        let mut game = quiet_game(7);
+        let id = taxi_across_24l(&mut game);
+        let events = step(&mut game, &[format!("cross {} 24R", id)]);
+        assert_eq!(
+            penalties(&events),
+            [&PenaltyReason::NoCrossing {
+                plane: id,
+                runway: "24R".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn pilot_crossing_without_a_clearance_is_logged_as_a_deviation() {
+        // Pilots stray 2% of the time; find a session where this one does
+        let incursion = (0..1000).find_map(|seed| {
+            let mut game = quiet_game(seed);
+            let departure = add_departure(&mut game, "24L");
+            game.process_command(format!("lineup {} 24L", departure));
+            let crossing = taxi_across_24l(&mut game);
+            let events = step(&mut game, &[]);
+            let incursion = incursions(&events).first().map(|i| (*i).clone())?;
+            assert!(!events.iter().any(|e| matches!(e, SimEvent::HoldingShort { .. })));
+            assert_eq!(incursion.plane, crossing);
+            Some(incursion)
+        });
+        let incursion = incursion.expect("no pilot strayed in 1000 sessions");
+        assert_eq!(incursion.severity, IncursionSeverity::C);
+        assert_eq!(incursion.cause, IncursionCause::PilotDeviation);
+    }
+
+    /// Step until `id` changes to a new status, for at most `ticks` ticks
+    fn next_status(game: &mut Game, id: &str, ticks: u64) -> Option<PlaneStatus> {
+        step_until(game, ticks, |e| match e {
+            SimEvent::PlaneStateChanged { plane, to, .. } if plane == id => Some(*to),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn arrival_taxis_in_to_the_gate_it_was_given() {
+        let mut game = quiet_game(7);
+        let id = add_arrival(&mut game, "B737", 60.0);
+        let events = step(&mut game, &[format!("gate {} B3", id), format!("land {} 24L", id)]);
+        assert!(cleared(&events, &id).contains(&Clearance::Gate { gate: "B3".into() }));
+        assert_eq!(game.airport.get_gate("B3").unwrap().occupant.as_deref(), Some(id.as_str()));
+
+        assert_eq!(next_status(&mut game, &id, 5), Some(PlaneStatus::TaxiingToGate));
+        assert_eq!(plane(&game, &id).ground.as_ref().unwrap().destination(), "B3");
+        assert_eq!(next_status(&mut game, &id, 20), Some(PlaneStatus::AtGate));
+        // Parked and gone, and the gate is free for the next one
+        assert!(!game.planes.iter().any(|p| p.id == id));
+        assert_eq!(game.airport.get_gate("B3").unwrap().occupant, None);
+    }
+
+    #[test]
+    fn arrival_without_a_gate_takes_the_nearest_free_one_that_fits() {
+        let mut game = quiet_game(7);
+        let id = add_arrival(&mut game, "B787", 60.0);
+        step(&mut game, &[format!("land {} 24L", id)]);
+        assert_eq!(next_status(&mut game, &id, 5), Some(PlaneStatus::TaxiingToGate));
+        let gate = plane(&game, &id).gate.clone().unwrap();
+        assert!(gate.starts_with('A'), "a widebody was sent to {}", gate);
+        assert_eq!(game.airport.get_gate(&gate).unwrap().occupant.as_deref(), Some(id.as_str()));
+    }
+
+    #[test]
+    fn gate_clearances_check_the_gate_exists_and_fits() {
+        let mut game = quiet_game(7);
+        let id = add_arrival(&mut game, "B737", 60.0);
+        let events = step(&mut game, &[format!("gate {} B9", id), format!("gate {} C2", id)]);
+        assert_eq!(
+            penalties(&events),
+            [
+                &PenaltyReason::UnknownGate("B9".into()),
+                &PenaltyReason::GateTooSmall {
+                    plane: id.clone(),
+                    gate: "C2".into(),
+                    size: GateSize::Regional,
+                    needed: GateSize::Narrowbody,
+                },
+            ]
+        );
+        assert_eq!(plane(&game, &id).gate, None);
+    }
+
+    #[test]
+    fn gate_clearances_are_for_arrivals_and_free_gates() {
+        let mut game = quiet_game(7);
+        let id = add_arrival(&mut game, "B737", 60.0);
+        let other = add_arrival(&mut game, "E175", 180.0);
+        let departure = add_on_ground(&mut game, "E175", "C1", PlaneStatus::Boarding);
+        let events = step(
+            &mut game,
+            &[
+                format!("gate {} B1", other),
+                format!("gate {} B1", id),
+                format!("gate {} B2", departure),
+            ],
+        );
+        assert_eq!(
+            penalties(&events),
+            [
+                &PenaltyReason::GateOccupied {
+                    plane: id.clone(),
+                    gate: "B1".into(),
+                    occupant: other.clone(),
+                },
+                &PenaltyReason::NotArriving(departure),
+            ]
+        );
+        assert_eq!(plane(&game, &other).gate.as_deref(), Some("B1"));
+        assert_eq!(plane(&game, &id).gate, None);
+    }
+
+    #[test]
+    fn pushed_back_plane_waits_on_the_ramp_and_frees_its_gate() {
+        let mut game = quiet_game(7);
+        let id = add_on_ground(&mut game, "B737", "B1", PlaneStatus::ReadyForPushback);
+        game.airport.assign_gate(&id, "B1");
+        game.planes.last_mut().unwrap().gate = Some("B1".into());
+
+        let events = step(&mut game, &[format!("pushback {}", id)]);
+        assert!(cleared(&events, &id).contains(&Clearance::Pushback));
+        assert_eq!(game.airport.get_gate("B1").unwrap().occupant, None);
+        assert_eq!(plane(&game, &id).gate, None);
+        // Held on the ramp off the gate until it is given a taxi clearance
+        for _ in 0..5 {
+            step(&mut game, &[]);
+        }
+        let pushed = plane(&game, &id);
+        assert_eq!(pushed.status, PlaneStatus::ReadyToTaxi);
+        assert_eq!(pushed.ground.as_ref().unwrap().at, "RAMP-B");
+
+        // Its gate can go straight to an arrival
+        let arrival = add_arrival(&mut game, "A320", 60.0);
+        let events = step(&mut game, &[format!("gate {} B1", arrival)]);
+        assert!(penalties(&events).is_empty(), "{:?}", events);
+    }
+
+    #[test]
+    fn arrival_with_no_gate_free_waits_on_a_remote_stand() {
+        let mut game = quiet_game(7);
+        let gates: Vec<String> = game.airport.gates.iter().map(|g| g.id.clone()).collect();
+        for gate in &gates {
+            game.airport.assign_gate(&format!("PARKED-{}", gate), gate);
+        }
+        let id = add_arrival(&mut game, "B737", 60.0);
+        step(&mut game, &[format!("land {} 24L", id)]);
+        assert_eq!(next_status(&mut game, &id, 5), Some(PlaneStatus::TaxiingToGate));
+        assert_eq!(next_status(&mut game, &id, 20), Some(PlaneStatus::HoldingForGate));
+        let waiting = plane(&game, &id);
+        assert_eq!(waiting.gate, None);
+        let spot = waiting.ground.as_ref().unwrap().at.clone();
+        assert!(game.airport.ground.ramp_spots(&game.airport.gates).contains(&spot.as_str()));
+
+        // Once a gate frees up it can be sent there
+        game.airport.release_gate("PARKED-B2");
+        let events = step(&mut game, &[format!("gate {} B2", id)]);
+        assert!(cleared(&events, &id).contains(&Clearance::Gate { gate: "B2".into() }));
+        assert_eq!(plane(&game, &id).status, PlaneStatus::TaxiingToGate);
+        assert_eq!(next_status(&mut game, &id, 10), Some(PlaneStatus::AtGate));
+    }
+
+    fn go_arounds(events: &[SimEvent]) -> Vec<(&str, &GoAroundReason)> {
+        events
+            .iter()
+            .filter_map(|e| match e {
+                SimEvent::GoAround { plane, reason, .. } => Some((plane.as_str(), reason)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sending_an_arrival_around_from_a_clear_runway_is_a_penalty() {
+        let mut game = quiet_game(7);
+        let id = add_arrival(&mut game, "B737", 60.0);
+        step(&mut game, &[format!("land {} 24R", id)]);
+        let events = step(&mut game, &[format!("goaround {}", id)]);
+        assert_eq!(go_arounds(&events), [(id.as_str(), &GoAroundReason::Instructed)]);
+        assert_eq!(
+            penalties(&events),
+            [&PenaltyReason::UnnecessaryGoAround {
+                plane: id.clone(),
+                runway: "24R".into(),
+            }]
+        );
+        assert_eq!(game.stats.go_arounds, 1);
+        assert_eq!(plane(&game, &id).status, PlaneStatus::MissedApproach);
+        assert_eq!(game.airport.get_runway("24R").unwrap().on_final, None);
+    }
+
+    #[test]
+    fn only_a_plane_on_final_can_go_around() {
+        let mut game = quiet_game(7);
+        let id = add_arrival(&mut game, "B737", 60.0);
+        let events = step(&mut game, &[format!("goaround {}", id)]);
+        assert_eq!(penalties(&events), [&PenaltyReason::NotOnFinal(id)]);
+        assert!(go_arounds(&events).is_empty());
+    }
+
+    #[test]
+    fn sending_an_arrival_around_from_a_blocked_runway_is_no_penalty() {
+        let mut game = quiet_game(7);
+        let id = add_arrival(&mut game, "B737", 60.0);
+        step(&mut game, &[format!("land {} 24R", id)]);
+        let tick = game.tick();
+        game.airport.get_runway_mut("24R").unwrap().hold_for_vehicle("SWEEPER", tick + 5);
+        let events = step(&mut game, &[format!("goaround {}", id)]);
+        assert_eq!(go_arounds(&events), [(id.as_str(), &GoAroundReason::Instructed)]);
+        assert!(penalties(&events).is_empty());
+    }
+
+    #[test]
+    fn arrival_goes_around_on_its_own_from_an_occupied_runway() {
+        let mut game = quiet_game(7);
+        let id = add_arrival(&mut game, "B737", 60.0);
+        step(&mut game, &[format!("land {} 24R", id)]);
+        let tick = game.tick();
+        game.airport.get_runway_mut("24R").unwrap().hold_for_vehicle("SWEEPER", tick + 5);
+        let events = step(&mut game, &[]);
+        let occupied = GoAroundReason::RunwayOccupied {
+            occupant: "SWEEPER".into(),
+        };
+        assert_eq!(go_arounds(&events), [(id.as_str(), &occupied)]);
+        assert!(penalties(&events).is_empty());
+        assert_eq!(game.stats.go_arounds, 1);
+        assert_eq!(game.stats.landings, 0);
+    }
+
+    #[test]
+    fn arrival_goes_around_on_its_own_when_the_runway_closes() {
+        let mut game = quiet_game(7);
+        let id = add_arrival(&mut game, "B737", 60.0);
+        step(&mut game, &[format!("land {} 24R", id)]);
+        game.set_metar("KPDX 181953Z 00000KT 1/8SM FG VV001").unwrap();
+        let events = step(&mut game, &[]);
+        assert!(events.contains(&SimEvent::RunwayClosed {
+            runway: "24R".into(),
+            reason: ClosureReason::LowVisibility,
+        }));
+        assert_eq!(go_arounds(&events), [(id.as_str(), &GoAroundReason::RunwayClosed)]);
+    }
+
+    #[test]
+    fn missed_approach_climbs_out_and_rejoins_the_arrivals() {
+        let mut game = quiet_game(7);
+        let id = add_arrival(&mut game, "B737", 60.0);
+        step(&mut game, &[format!("land {} 24R", id)]);
+        step(&mut game, &[format!("goaround {}", id)]);
+        let flight = plane(&game, &id).flight.as_ref().unwrap();
+        assert_eq!(flight.target_altitude_ft, 4000.0);
+        assert_eq!(flight.direct_to.as_ref().map(|d| d.fix.as_str()), Some("DELTA"));
+
+        assert_eq!(next_status(&mut game, &id, 30), Some(PlaneStatus::InAir));
+        // Then back round to land again
+        assert_eq!(next_status(&mut game, &id, 30), Some(PlaneStatus::ReadyToLand));
+        let events = step(&mut game, &[format!("land {} 24R", id)]);
+        assert!(cleared(&events, &id).contains(&Clearance::Land { runway: "24R".into() }));
+    }
+
+    /// Leave `id` with `minutes` of fuel
+    fn set_endurance(game: &mut Game, id: &str, minutes: f64) {
+        let plane = find_plane(&mut game.planes, id).unwrap();
+        plane.fuel_lb = plane.aircraft.fuel_burn_lb_per_min * minutes;
+    }
+
+    fn fuel_declarations(events: &[SimEvent]) -> Vec<(&str, FuelState)> {
+        events
+            .iter()
+            .filter_map(|e| match e {
+                SimEvent::FuelDeclared { plane, state, .. } => Some((plane.as_str(), *state)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn crew_short_of_fuel_declares_minimum_then_mayday() {
+        let mut game = quiet_game(7);
+        let id = add_arrival(&mut game, "A320", 60.0);
+        set_endurance(&mut game, &id, fuel::MINIMUM_FUEL_MINUTES + 0.5);
+        let events = step(&mut game, &[]);
+        assert_eq!(fuel_declarations(&events), [(id.as_str(), FuelState::Minimum)]);
+        assert!(!plane(&game, &id).has_priority());
+
+        // Holding burns the rest of its margin
+        let mut declared = Vec::new();
+        step(&mut game, &[format!("hold {} ALPHA", id)]);
+        for _ in 0..10 {
+            let events = step(&mut game, &[]);
+            declared.extend(fuel_declarations(&events).into_iter().map(|(p, s)| (p.to_string(), s)));
+        }
+        assert_eq!(declared, [(id.clone(), FuelState::Mayday)]);
+        assert!(plane(&game, &id).has_priority());
+    }
+
+    #[test]
+    fn mayday_fuel_lands_ahead_of_normal_traffic() {
+        let mut game = quiet_game(7);
+        let normal = add_arrival(&mut game, "B737", 180.0);
+        let short = add_arrival(&mut game, "A320", 60.0);
+        set_endurance(&mut game, &short, fuel::MAYDAY_FUEL_MINUTES);
+        let events = step(&mut game, &[]);
+        let minutes = (fuel::MAYDAY_FUEL_MINUTES - 1.0) as u32;
+        assert!(events.contains(&SimEvent::FuelDeclared {
+            plane: short.clone(),
+            state: FuelState::Mayday,
+            minutes,
+        }));
+        assert_eq!(game.sequence.order()[0], short);
+
+        let events = step(&mut game, &[format!("land {} 24R", normal)]);
+        assert_eq!(
+            penalties(&events),
+            [&PenaltyReason::NotPrioritised {
+                plane: normal.clone(),
+                priority: short.clone(),
+            }]
+        );
+        let events = step(&mut game, &[format!("land {} 24L", short), format!("land {} 24R", normal)]);
+        assert!(penalties(&events).is_empty(), "{:?}", events);
+    }
+
+    #[test]
+    fn running_dry_in_the_air_ends_the_session() {
+        let mut game = quiet_game(7);
+        let id = add_arrival(&mut game, "A320", 60.0);
+        set_endurance(&mut game, &id, 0.5);
+        let events = step(&mut game, &[]);
+        assert!(events.iter().any(|e| matches!(
+            e,
+            SimEvent::Incident {
+                incident: Incident::FuelExhaustion { plane },
+                ..
+            } if *plane == id
+        )));
+        assert!(events.iter().any(|e| matches!(
+            e,
+            SimEvent::GameOver {
+                reason: GameOverReason::FuelExhaustion { plane },
+                ..
+            } if *plane == id
+        )));
+        assert!(game.is_game_over());
+        // Nothing more happens
+        assert!(step(&mut game, &[format!("land {} 24L", id)]).is_empty());
+    }
+
+    fn score_changes(events: &[SimEvent]) -> Vec<i32> {
+        events
+            .iter()
+            .filter_map(|e| match e {
+                SimEvent::ScoreChanged { delta, .. } => Some(*delta),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Land `id` on `runway` and step until it has touched down, returning
+    /// every event on the way
+    fn land(game: &mut Game, id: &str, runway: &str) -> Vec<SimEvent> {
+        let mut events = step(game, &[format!("land {} {}", id, runway)]);
+        for _ in 0..flight::FINAL_APPROACH_TICKS {
+            events.extend(step(game, &[]));
+        }
+        assert_eq!(plane(game, id).status, PlaneStatus::TaxiingToGate, "{:?}", events);
+        events
+    }
+
+    #[test]
+    fn departure_with_an_emergency_turns_back_to_land() {
+        let mut game = quiet_game(7);
+        let id = add_departure(&mut game, "24L");
+        step(&mut game, &[format!("takeoff {} 24L", id)]);
+        game.declare_emergency(&id, EmergencyKind::EngineFailure).unwrap();
+        let events = game.drain_events();
+        assert_eq!(
+            events,
+            [
+                SimEvent::PlaneStateChanged {
+                    plane: id.clone(),
+                    from: PlaneStatus::Departing,
+                    to: PlaneStatus::InAir,
+                },
+                SimEvent::Emergency {
+                    plane: id.clone(),
+                    kind: EmergencyKind::EngineFailure,
+                    returning: true,
+                },
+            ]
+        );
+        assert_eq!(game.stats.emergencies, 1);
+        step(&mut game, &[]);
+        assert_eq!(game.sequence.order().first(), Some(&id));
+    }
+
+    #[test]
+    fn emergencies_must_fit_the_phase_of_flight() {
+        let mut game = quiet_game(7);
+        let arrival = add_arrival(&mut game, "B737", 60.0);
+        let departure = add_departure(&mut game, "24L");
+        let parked = add_on_ground(&mut game, "E175", "C1", PlaneStatus::Boarding);
+        step(&mut game, &[format!("takeoff {} 24L", departure)]);
+
+        let wrong_phase = |plane: &str, kind| EmergencyError::WrongPhase {
+            plane: plane.to_string(),
+            kind,
+        };
+        assert_eq!(
+            game.declare_emergency(&arrival, EmergencyKind::BirdStrike),
+            Err(wrong_phase(&arrival, EmergencyKind::BirdStrike))
+        );
+        assert_eq!(
+            game.declare_emergency(&departure, EmergencyKind::Diversion),
+            Err(wrong_phase(&departure, EmergencyKind::Diversion))
+        );
+        assert_eq!(
+            game.declare_emergency(&parked, EmergencyKind::Medical),
+            Err(wrong_phase(&parked, EmergencyKind::Medical))
+        );
+        assert_eq!(
+            game.declare_emergency("NOBODY1", EmergencyKind::Medical),
+            Err(EmergencyError::UnknownPlane("NOBODY1".into()))
+        );
+        assert_eq!(game.declare_emergency(&arrival, EmergencyKind::Medical), Ok(()));
+        assert_eq!(
+            game.declare_emergency(&arrival, EmergencyKind::EngineFailure),
+            Err(EmergencyError::AlreadyDeclared(arrival.clone()))
+        );
+        assert_eq!(game.stats.emergencies, 1);
+    }
+
+    #[test]
+    fn bird_strike_closes_the_runway_for_a_sweep() {
+        let mut game = quiet_game(7);
+        let struck = add_departure(&mut game, "24L");
+        let next = add_departure(&mut game, "24L");
+        step(&mut game, &[format!("takeoff {} 24L", struck)]);
+        game.declare_emergency(&struck, EmergencyKind::BirdStrike).unwrap();
+        assert!(game.drain_events().iter().any(|e| matches!(e, SimEvent::Info(m) if m.contains("runway 24L closed"))));
+
+        let events = step(&mut game, &[format!("takeoff {} 24L", next)]);
+        assert_eq!(
+            penalties(&events),
+            [&PenaltyReason::RunwayOccupied {
+                plane: next.clone(),
+                runway: "24L".into(),
+                occupant: emergency::SWEEP_VEHICLE.into(),
+            }]
+        );
+        for _ in 1..emergency::SWEEP_TICKS {
+            step(&mut game, &[]);
+        }
+        let events = step(&mut game, &[format!("takeoff {} 24L", next)]);
+        assert!(cleared(&events, &next).contains(&Clearance::Takeoff { runway: "24L".into() }));
+    }
+
+    #[test]
+    fn alerting_services_scores_once_and_only_for_an_emergency() {
+        let mut game = quiet_game(7);
+        let sick = add_arrival(&mut game, "B737", 60.0);
+        let well = add_arrival(&mut game, "A320", 180.0);
+        game.declare_emergency(&sick, EmergencyKind::Medical).unwrap();
+        let events = step(&mut game, &[format!("alert {}", sick)]);
+        assert!(cleared(&events, &sick).contains(&Clearance::Alert {
+            services: Services::Paramedics,
+        }));
+        assert_eq!(score_changes(&events), [5]);
+        let events = step(&mut game, &[format!("alert {}", sick)]);
+        assert_eq!(score_changes(&events), [0]);
+        let events = step(&mut game, &[format!("alert {}", well)]);
+        assert_eq!(penalties(&events), [&PenaltyReason::NoEmergency(well)]);
+    }
+
+    #[test]
+    fn emergency_landing_with_services_waiting_earns_a_bonus() {
+        let mut game = quiet_game(7);
+        let id = add_arrival(&mut game, "B737", 60.0);
+        game.declare_emergency(&id, EmergencyKind::EngineFailure).unwrap();
+        step(&mut game, &[format!("alert {}", id)]);
+        let events = land(&mut game, &id, "24L");
+        assert!(penalties(&events).is_empty(), "{:?}", events);
+        let landing = score_changes(&events);
+        assert_eq!(landing.len(), 1);
+        assert!(landing[0] > 10, "landing scored {}", landing[0]);
+        assert!(plane(&game, &id).emergency.is_none());
+    }
+
+    #[test]
+    fn emergency_landing_with_nobody_waiting_is_a_penalty() {
+        let mut game = quiet_game(7);
+        let id = add_arrival(&mut game, "B737", 60.0);
+        game.declare_emergency(&id, EmergencyKind::EngineFailure).unwrap();
+        let events = land(&mut game, &id, "24L");
+        assert_eq!(
+            penalties(&events),
+            [&PenaltyReason::ServicesNotAlerted {
+                plane: id,
+                services: Services::FireRescue,
+            }]
+        );
+        assert_eq!(score_changes(&events), [10, -5]);
+    }
+
+    #[test]
+    fn hydraulic_failure_needs_more_runway_and_a_tow() {
+        let mut game = quiet_game(7);
+        let id = add_arrival(&mut game, "E175", 60.0);
+        game.declare_emergency(&id, EmergencyKind::HydraulicFailure).unwrap();
+        let events = step(&mut game, &[format!("alert {}", id), format!("land {} 18", id)]);
+        assert_eq!(
+            penalties(&events),
+            [&PenaltyReason::RunwayTooShort {
+                plane: id.clone(),
+                runway: "18".into(),
+                length_ft: 6000,
+                required_ft: 6600,
+            }]
+        );
+        land(&mut game, &id, "24L");
+        // Stopped on the runway for its landing roll and the tow
+        let runway = game.airport.get_runway("24L").unwrap();
+        let landed = runway.last_movement.as_ref().unwrap().tick;
+        let until = landed + separation::occupancy_ticks(RunwayOperation::Landing) + emergency::TOW_TICKS;
+        assert_eq!(runway.occupant(until - 1), Some(id.as_str()));
+        assert_eq!(runway.occupant(until), None);
+    }
+
+    #[test]
+    fn diversion_has_priority_but_needs_no_services() {
+        let mut game = quiet_game(7);
+        let normal = add_arrival(&mut game, "B737", 180.0);
+        let diverted = add_arrival(&mut game, "A320", 60.0);
+        game.declare_emergency(&diverted, EmergencyKind::Diversion).unwrap();
+        let events = step(&mut game, &[format!("alert {}", diverted)]);
+        assert_eq!(penalties(&events), [&PenaltyReason::NoEmergency(diverted.clone())]);
+        assert_eq!(game.sequence.order()[0], diverted);
+        let events = step(&mut game, &[format!("sequence {} after {}", diverted, normal)]);
+        assert_eq!(
+            penalties(&events),
+            [&PenaltyReason::NotPrioritised {
+                plane: normal,
+                priority: diverted.clone(),
+            }]
+        );
+        let events = land(&mut game, &diverted, "24L");
+        assert!(penalties(&events).is_empty(), "{:?}", events);
+    }
+
+    #[test]
+    fn diversions_arrive_with_their_emergency_declared() {
+        let diversion = (0..100).find_map(|seed| {
+            let events: Vec<SimEvent> = routine_session(seed, 120).into_iter().map(|(_, e)| e).collect();
+            events.windows(2).find_map(|pair| match pair {
+                [
+                    SimEvent::PlaneSpawned {
+                        plane, arriving: true, ..
+                    },
+                    SimEvent::Emergency {
+                        plane: declared,
+                        kind: EmergencyKind::Diversion,
+                        returning,
+                    },
+                ] if plane == declared => Some(*returning),
+                _ => None,
+            })
+        });
+        assert_eq!(diversion, Some(false), "no diversion in 100 sessions");
+    }
+
+    fn hold_level(game: &Game, id: &str) -> Option<u32> {
+        plane(game, id).flight.as_ref()?.holding.as_ref().map(|h| h.level)
+    }
+
+    #[test]
+    fn holds_stack_up_from_the_bottom_and_step_down() {
+        let mut game = quiet_game(7);
+        let first = add_arrival(&mut game, "B737", 60.0);
+        let second = add_arrival(&mut game, "A320", 300.0);
+        let events = step(&mut game, &[format!("hold {} ALPHA", first), format!("hold {} ALPHA", second)]);
+        let hold_at = |altitude_ft| Clearance::Hold {
+            fix: "ALPHA".into(),
+            altitude_ft,
+        };
+        assert_eq!(cleared(&events, &first), [hold_at(5000)]);
+        assert_eq!(cleared(&events, &second), [hold_at(6000)]);
+        assert!(events.contains(&SimEvent::PlaneStateChanged {
+            plane: second.clone(),
+            from: PlaneStatus::ReadyToLand,
+            to: PlaneStatus::Holding,
+        }));
+        assert!(penalties(&events).is_empty());
+
+        // Vectored out of the bottom, the one above takes its level
+        let events = step(&mut game, &[format!("heading {} 180", first)]);
+        assert!(events.contains(&SimEvent::PlaneStateChanged {
+            plane: first.clone(),
+            from: PlaneStatus::Holding,
+            to: PlaneStatus::InAir,
+        }));
+        assert_eq!(hold_level(&game, &first), None);
+        assert_eq!(hold_level(&game, &second), Some(0));
+        assert_eq!(plane(&game, &second).flight.as_ref().unwrap().target_altitude_ft, 5000.0);
+    }
+
+    #[test]
+    fn full_hold_takes_no_more_planes() {
+        let mut game = quiet_game(7);
+        let arrivals: Vec<String> = [0.0, 72.0, 144.0, 216.0, 288.0]
+            .into_iter()
+            .map(|bearing| add_arrival(&mut game, "E175", bearing))
+            .collect();
+        let commands: Vec<String> = arrivals.iter().map(|id| format!("hold {} BRAVO", id)).collect();
+        let events = step(&mut game, &commands);
+        assert_eq!(penalties(&events), [&PenaltyReason::HoldFull("BRAVO".into())]);
+        let levels: Vec<Option<u32>> = arrivals.iter().map(|id| hold_level(&game, id)).collect();
+        assert_eq!(levels, [Some(0), Some(1), Some(2), Some(3), None]);
+    }
+
+    #[test]
+    fn holds_need_a_published_hold_at_a_known_fix() {
+        let mut game = quiet_game(7);
+        let id = add_arrival(&mut game, "B737", 60.0);
+        let events = step(&mut game, &[format!("hold {} ZULU", id), format!("hold {} DELTA", id)]);
+        assert_eq!(
+            penalties(&events),
+            [
+                &PenaltyReason::UnknownFix("ZULU".into()),
+                &PenaltyReason::NoPublishedHold("DELTA".into()),
+            ]
+        );
+        assert_eq!(plane(&game, &id).status, PlaneStatus::ReadyToLand);
+    }
+
+    #[test]
+    fn only_arrivals_not_yet_cleared_to_land_can_hold() {
+        let mut game = quiet_game(7);
+        let departure = add_departure(&mut game, "24L");
+        let arrival = add_arrival(&mut game, "B737", 60.0);
+        step(&mut game, &[format!("takeoff {} 24L", departure), format!("land {} 24R", arrival)]);
+        let events = step(&mut game, &[format!("hold {} ALPHA", departure), format!("hold {} ALPHA", arrival)]);
+        assert_eq!(
+            penalties(&events),
+            [&PenaltyReason::CannotHold(departure), &PenaltyReason::OnFinal(arrival)]
+        );
+    }
+
+    #[test]
+    fn arrivals_land_in_sequence_unless_resequenced() {
+        let mut game = quiet_game(7);
+        let first = add_arrival(&mut game, "B737", 60.0);
+        let second = add_arrival(&mut game, "A320", 180.0);
+        assert_eq!(game.sequence.order(), [first.clone(), second.clone()]);
+
+        let events = step(&mut game, &[format!("land {} 24R", second)]);
+        assert_eq!(
+            penalties(&events),
+            [&PenaltyReason::OutOfSequence {
+                plane: second.clone(),
+                ahead: first.clone(),
+            }]
+        );
+
+        let events = step(&mut game, &[format!("sequence {} after {}", first, second)]);
+        assert!(cleared(&events, &first).contains(&Clearance::Sequence {
+            after: second.clone(),
+            number: 2,
+        }));
+        assert_eq!(game.sequence.order(), [second.clone(), first.clone()]);
+        let events = step(&mut game, &[format!("land {} 24R", second)]);
+        assert!(penalties(&events).is_empty(), "{:?}", events);
+    }
+
+    #[test]
+    fn only_airborne_arrivals_can_be_sequenced() {
+        let mut game = quiet_game(7);
+        let arrival = add_arrival(&mut game, "B737", 60.0);
+        let departure = add_departure(&mut game, "24L");
+        let events = step(&mut game, &[format!("sequence {} after {}", arrival, departure)]);
+        assert_eq!(penalties(&events), [&PenaltyReason::NotInSequence(departure)]);
+    }
+}
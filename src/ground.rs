@@ -0,0 +1,445 @@
+use crate::airport::{Gate, Runway};
+use serde::Deserialize;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::fmt;
+
+// --- GROUND MODULE ---
+// The airport surface: a graph of taxiways joining gates and runway holding
+// points, loaded with the airport layout. Taxi routes are searched over the
+// graph and planes roll along them at taxi speed.
+
+/// Distance covered in one tick at a 15 kt taxi
+pub const TAXI_FT_PER_TICK: f64 = 1500.0;
+
+/// Chance a pilot reaching a runway crosses it without a clearance
+pub const DEVIATION_CHANCE: f64 = 0.02;
+
+/// A point on the surface: a gate, a taxiway intersection or a holding point
+#[derive(Debug, Clone, Deserialize)]
+pub struct Node {
+    pub id: String,
+    pub x_ft: f64,
+    pub y_ft: f64,
+    /// Set on holding points: the runway this node holds short of. Landing
+    /// traffic also vacates the runway here.
+    #[serde(default)]
+    pub runway: Option<String>,
+    /// Set where a taxiway crosses a runway: the runway this node lies on.
+    /// Entering it needs a crossing clearance.
+    #[serde(default)]
+    pub on_runway: Option<String>,
+}
+
+/// A named chain of segments. A taxiway may be listed in several pieces.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Taxiway {
+    pub name: String,
+    /// Consecutive nodes are joined by a segment of this taxiway
+    pub nodes: Vec<String>,
+    /// Apron lanes may be used without being named in a taxi clearance
+    #[serde(default)]
+    pub ramp: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct GroundLayout {
+    #[serde(default)]
+    pub nodes: Vec<Node>,
+    #[serde(default)]
+    pub taxiways: Vec<Taxiway>,
+}
+
+/// Where a taxi clearance leads
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaxiTarget {
+    Runway(String),
+    Gate(String),
+}
+
+impl fmt::Display for TaxiTarget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TaxiTarget::Runway(id) => write!(f, "runway {}", id),
+            TaxiTarget::Gate(id) => write!(f, "gate {}", id),
+        }
+    }
+}
+
+/// A route found over the graph: the nodes to reach, in order
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaxiRoute {
+    pub nodes: VecDeque<String>,
+    pub length_ft: f64,
+}
+
+impl GroundLayout {
+    pub fn node(&self, id: &str) -> Option<&Node> {
+        self.nodes.iter().find(|n| n.id.eq_ignore_ascii_case(id))
+    }
+
+    /// Holding points for runway `id`
+    pub fn holding_points<'a>(&'a self, runway: &'a str) -> impl Iterator<Item = &'a Node> + 'a {
+        self.nodes.iter().filter(move |n| {
+            n.runway.as_deref().is_some_and(|r| r.eq_ignore_ascii_case(runway))
+        })
+    }
+
+    /// The runway a node lies on, if it is a crossing
+    pub fn runway_at(&self, node: &str) -> Option<&str> {
+        self.node(node)?.on_runway.as_deref()
+    }
+
+    /// Spots on the ramp lanes, off the gates and the taxiways, where a
+    /// plane can wait
+    pub fn ramp_spots(&self, gates: &[Gate]) -> Vec<&str> {
+        let mut spots: Vec<&str> = self
+            .taxiways
+            .iter()
+            .filter(|t| t.ramp)
+            .flat_map(|t| t.nodes.iter().map(String::as_str))
+            .filter(|id| !gates.iter().any(|g| g.id.eq_ignore_ascii_case(id)))
+            .filter(|id| !self.taxiways.iter().any(|t| !t.ramp && t.nodes.iter().any(|n| n == id)))
+            .collect();
+        spots.sort_unstable();
+        spots.dedup();
+        spots
+    }
+
+    /// Where a plane pushed back from gate `id` ends up: the ramp node next
+    /// to it
+    pub fn pushback_spot(&self, gate: &str) -> Option<&str> {
+        self.segments().find(|(from, _, _)| from.eq_ignore_ascii_case(gate)).map(|(_, to, _)| to)
+    }
+
+    /// Names of the taxiways a clearance can route along
+    pub fn taxiway_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self
+            .taxiways
+            .iter()
+            .filter(|t| !t.ramp)
+            .map(|t| t.name.as_str())
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// The taxiway joining two adjacent nodes
+    pub fn taxiway_between(&self, a: &str, b: &str) -> Option<&Taxiway> {
+        self.segments().find(|(from, to, _)| *from == a && *to == b).map(|(_, _, t)| t)
+    }
+
+    /// Straight-line length of the segment between two nodes
+    pub fn distance_ft(&self, a: &str, b: &str) -> f64 {
+        match (self.node(a), self.node(b)) {
+            (Some(a), Some(b)) => (a.x_ft - b.x_ft).hypot(a.y_ft - b.y_ft),
+            _ => 0.0,
+        }
+    }
+
+    /// Every segment, in both directions
+    fn segments(&self) -> impl Iterator<Item = (&str, &str, &Taxiway)> {
+        self.taxiways.iter().flat_map(|taxiway| {
+            taxiway.nodes.windows(2).flat_map(move |pair| {
+                [
+                    (pair[0].as_str(), pair[1].as_str(), taxiway),
+                    (pair[1].as_str(), pair[0].as_str(), taxiway),
+                ]
+            })
+        })
+    }
+
+    /// Shortest route from `from` to any of `targets`. With `via`, the route
+    /// must follow those taxiways in that order, using ramp lanes only before
+    /// the first and after the last; without it any taxiway may be used.
+    pub fn route(&self, from: &str, targets: &[&str], via: &[String]) -> Option<TaxiRoute> {
+        let from = self.node(from)?.id.as_str();
+        let mut best: HashMap<(&str, usize), u64> = HashMap::new();
+        let mut previous: HashMap<(&str, usize), (&str, usize)> = HashMap::new();
+        let mut queue = BinaryHeap::new();
+        best.insert((from, 0), 0);
+        queue.push(Reverse((0, from, 0)));
+
+        while let Some(Reverse((cost, node, leg))) = queue.pop() {
+            if best.get(&(node, leg)).is_some_and(|&known| known < cost) {
+                continue;
+            }
+            if leg == via.len() && targets.iter().any(|t| t.eq_ignore_ascii_case(node)) {
+                return Some(self.rebuild(&previous, (node, leg), cost));
+            }
+            for (_, next, taxiway) in self.segments().filter(|(a, _, _)| *a == node) {
+                let Some(next_leg) = next_leg(taxiway, leg, via) else {
+                    continue;
+                };
+                let next_cost = cost + self.distance_ft(node, next).round() as u64;
+                let state = (next, next_leg);
+                if best.get(&state).is_none_or(|&known| next_cost < known) {
+                    best.insert(state, next_cost);
+                    previous.insert(state, (node, leg));
+                    queue.push(Reverse((next_cost, next, next_leg)));
+                }
+            }
+        }
+        None
+    }
+
+    fn rebuild<'a>(
+        &self,
+        previous: &HashMap<(&'a str, usize), (&'a str, usize)>,
+        mut state: (&'a str, usize),
+        cost: u64,
+    ) -> TaxiRoute {
+        let mut nodes = VecDeque::new();
+        while let Some(&before) = previous.get(&state) {
+            nodes.push_front(state.0.to_string());
+            state = before;
+        }
+        TaxiRoute {
+            nodes,
+            length_ft: cost as f64,
+        }
+    }
+
+    /// Check every taxiway, gate and runway is joined up to the graph
+    pub fn validate(&self, runways: &[Runway], gates: &[Gate]) -> Result<(), String> {
+        for taxiway in &self.taxiways {
+            let known = |id: &&String| self.nodes.iter().any(|n| n.id == **id);
+            if let Some(missing) = taxiway.nodes.iter().find(|id| !known(id)) {
+                return Err(format!("taxiway {} uses unknown node {}", taxiway.name, missing));
+            }
+        }
+        for node in &self.nodes {
+            if let Some(runway) = &node.runway
+                && !runways.iter().any(|r| r.id.eq_ignore_ascii_case(runway))
+            {
+                return Err(format!("node {} holds short of unknown runway {}", node.id, runway));
+            }
+            if let Some(runway) = &node.on_runway
+                && !runways.iter().any(|r| r.id.eq_ignore_ascii_case(runway))
+            {
+                return Err(format!("node {} lies on unknown runway {}", node.id, runway));
+            }
+        }
+        if let Some(runway) = runways.iter().find(|r| self.holding_points(&r.id).next().is_none()) {
+            return Err(format!("runway {} has no holding point", runway.id));
+        }
+        if let Some(gate) = gates.iter().find(|g| self.node(&g.id).is_none()) {
+            return Err(format!("gate {} is not on the taxiway graph", gate.id));
+        }
+        Ok(())
+    }
+}
+
+/// Which leg of a `via` clearance a segment of `taxiway` puts the plane on,
+/// or `None` if the clearance doesn't allow it. Leg k means the first k
+/// taxiways named have been joined.
+fn next_leg(taxiway: &Taxiway, leg: usize, via: &[String]) -> Option<usize> {
+    if via.is_empty() {
+        return Some(0);
+    }
+    if leg > 0 && taxiway.name.eq_ignore_ascii_case(&via[leg - 1]) {
+        Some(leg)
+    } else if leg < via.len() && taxiway.name.eq_ignore_ascii_case(&via[leg]) {
+        Some(leg + 1)
+    } else if taxiway.ramp && (leg == 0 || leg == via.len()) {
+        Some(leg)
+    } else {
+        None
+    }
+}
+
+/// Where a plane is on the surface
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroundPosition {
+    /// Last node reached
+    pub at: String,
+    /// Nodes still to reach, in order
+    pub route: VecDeque<String>,
+    /// Distance covered from `at` toward the next node
+    pub along_ft: f64,
+    /// Segments rolled along during the last tick, as (from, to)
+    pub recent: Vec<(String, String)>,
+    /// Runways the plane may cross on its way
+    pub cleared_to_cross: Vec<String>,
+    /// The runway the plane has stopped short of, waiting to cross
+    pub holding_for: Option<String>,
+    /// A runway the pilot has just started across without a clearance,
+    /// until the incursion is logged
+    pub deviation: Option<String>,
+}
+
+impl GroundPosition {
+    /// Standing still at a node
+    pub fn at(node: &str) -> Self {
+        GroundPosition {
+            at: node.to_string(),
+            route: VecDeque::new(),
+            along_ft: 0.0,
+            recent: Vec::new(),
+            cleared_to_cross: Vec::new(),
+            holding_for: None,
+            deviation: None,
+        }
+    }
+
+    /// The node a new route has to start from: when between nodes the plane
+    /// carries on to the next one first
+    pub fn route_origin(&self) -> &str {
+        match self.route.front() {
+            Some(next) if self.along_ft > 0.0 => next,
+            _ => &self.at,
+        }
+    }
+
+    /// Follow `route` from `route_origin`
+    pub fn follow(&mut self, route: TaxiRoute) {
+        let mut nodes = route.nodes;
+        if self.along_ft > 0.0
+            && let Some(next) = self.route.front()
+        {
+            nodes.push_front(next.clone());
+        }
+        self.route = nodes;
+        self.holding_for = None;
+    }
+
+    /// Runways the rest of the route crosses, in order
+    pub fn crossings_ahead<'a>(&'a self, layout: &'a GroundLayout) -> Vec<&'a str> {
+        let mut crossings = Vec::new();
+        let mut previous = layout.runway_at(&self.at);
+        for node in &self.route {
+            let runway = layout.runway_at(node);
+            if let Some(id) = runway
+                && runway != previous
+            {
+                crossings.push(id);
+            }
+            previous = runway;
+        }
+        crossings
+    }
+
+    /// Allow the plane across `runway`
+    pub fn clear_to_cross(&mut self, runway: &str) {
+        self.cleared_to_cross.push(runway.to_string());
+        if self.holding_for.as_deref() == Some(runway) {
+            self.holding_for = None;
+        }
+    }
+
+    /// Whether the plane is on `runway`, or cleared onto it
+    pub fn is_crossing(&self, layout: &GroundLayout, runway: &str) -> bool {
+        let on = |node: &str| layout.runway_at(node) == Some(runway);
+        let entering = self.along_ft > 0.0 && self.route.front().is_some_and(|next| on(next));
+        on(&self.at) || entering || self.cleared_to_cross.iter().any(|r| r == runway)
+    }
+
+    /// Whether the plane has reached the end of its route
+    pub fn arrived(&self) -> bool {
+        self.route.is_empty()
+    }
+
+    /// Distance still to roll to the end of the route
+    pub fn remaining_ft(&self, layout: &GroundLayout) -> f64 {
+        let mut from = self.at.as_str();
+        let mut remaining = -self.along_ft;
+        for node in &self.route {
+            remaining += layout.distance_ft(from, node);
+            from = node;
+        }
+        remaining.max(0.0)
+    }
+
+    /// The node the route ends at
+    pub fn destination(&self) -> &str {
+        self.route.back().unwrap_or(&self.at)
+    }
+
+    /// Roll up to `distance_ft` along the route, stopping short of any
+    /// runway the plane isn't cleared to cross
+    pub fn advance(&mut self, layout: &GroundLayout, mut distance_ft: f64) {
+        while let Some(next) = self.route.front() {
+            let current = layout.runway_at(&self.at);
+            if self.along_ft == 0.0
+                && let Some(runway) = layout.runway_at(next)
+                && current != Some(runway)
+                && !self.cleared_to_cross.iter().any(|r| r == runway)
+            {
+                self.holding_for = Some(runway.to_string());
+                return;
+            }
+
+            self.recent.push((self.at.clone(), next.clone()));
+            let remaining = layout.distance_ft(&self.at, next) - self.along_ft;
+            if distance_ft < remaining {
+                self.along_ft += distance_ft;
+                return;
+            }
+            distance_ft -= remaining;
+            self.at = self.route.pop_front().unwrap_or_default();
+            self.along_ft = 0.0;
+
+            // Off the runway: that crossing is done
+            if let Some(left) = current
+                && layout.runway_at(&self.at) != Some(left)
+            {
+                self.cleared_to_cross.retain(|r| r != left);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::airport::Airport;
+
+    fn nodes(route: &TaxiRoute) -> Vec<&str> {
+        route.nodes.iter().map(String::as_str).collect()
+    }
+
+    #[test]
+    fn finds_the_shortest_route() {
+        let airport = Airport::new();
+        let route = airport.ground.route("a1", &["HS-24L"], &[]).unwrap();
+        assert_eq!(
+            nodes(&route),
+            ["RAMP-A", "RAMP-A2", "A-RA", "A-D", "A-RC", "A-B", "HS-24L"]
+        );
+        let length: f64 = ["A1", "RAMP-A", "RAMP-A2", "A-RA", "A-D", "A-RC", "A-B", "HS-24L"]
+            .windows(2)
+            .map(|pair| airport.ground.distance_ft(pair[0], pair[1]).round())
+            .sum();
+        assert_eq!(route.length_ft, length);
+    }
+
+    #[test]
+    fn follows_the_named_taxiways_in_order() {
+        let airport = Airport::new();
+        let via = ["A".to_string(), "B".to_string()];
+        let route = airport.ground.route("C1", &["HS-24L"], &via).unwrap();
+        assert_eq!(nodes(&route), ["RAMP-C", "RAMP-C2", "A-RC", "A-B", "HS-24L"]);
+
+        // Taxiway D leads to runway 18, not 24L, and the ramp lanes only
+        // reach taxiway C along A
+        assert_eq!(airport.ground.route("C1", &["HS-24L"], &["D".to_string()]), None);
+        assert_eq!(
+            airport.ground.route("A1", &["HS-24L"], &["C".to_string(), "A".to_string()]),
+            None
+        );
+    }
+
+    #[test]
+    fn reaches_the_nearest_of_several_targets() {
+        let airport = Airport::new();
+        let route = airport.ground.route("HS-18", &["B1", "C1"], &[]).unwrap();
+        assert_eq!(route.nodes.back().map(String::as_str), Some("B1"));
+    }
+
+    #[test]
+    fn has_no_route_from_an_unknown_node() {
+        let airport = Airport::new();
+        assert_eq!(airport.ground.route("Z9", &["HS-24L"], &[]), None);
+    }
+}
@@ -0,0 +1,23 @@
+// --- ATC SIMULATOR LIBRARY ---
+// The simulation engine. The terminal game in main.rs is one frontend for it;
+// tests, benches and other tools can drive the same `Game` directly.
+
+pub mod aircraft;
+pub mod airport;
+pub mod command;
+pub mod emergency;
+pub mod event;
+pub mod flight;
+pub mod flight_plan;
+pub mod fuel;
+pub mod game;
+pub mod ground;
+pub mod metar;
+pub mod navdata;
+pub mod plane;
+pub mod replay;
+pub mod scheduler;
+pub mod script;
+pub mod sequence;
+pub mod separation;
+pub mod weather;
@@ -3,13 +3,18 @@
 
 #![allow(clippy::all)]
 
-// Declare the modules that Rust will look for in other files
-mod airport;
-mod flight_plan;
-mod game;
-mod plane;
+// The engine lives in the library crate (src/lib.rs); this binary is only
+// the menu, the ASCII art and the input loop.
+use atc_simulator::aircraft::AircraftDatabase;
+use atc_simulator::airport::Airport;
+use atc_simulator::event::SimEvent;
+use atc_simulator::game;
+use atc_simulator::navdata::NavDatabase;
+use atc_simulator::replay::Replay;
+use atc_simulator::script::Script;
+use atc_simulator::separation::IncursionSeverity;
 
-use std::io::{self, Write};
+use std::io::{self, Read, Write};
 use std::thread;
 use std::time::Duration;
 
@@ -94,15 +99,52 @@ fn show_how_to_play() {
     println!(r"movements and penalties for mistakes.");
     println!(r"");
     println!(r"COMMANDS:");
-    println!(r"  pushback [id]     - Clears a 'Ready for Pushback' plane to taxi.");
+    println!(r"  pushback [id]     - Pushes a 'Ready for Pushback' plane off its gate.");
     println!(r"                      (e.g., 'pushback SWA123')");
     println!(r"");
+    println!(r"  taxi [id] [rw/gate] via [twy..] - Taxis along the named taxiways.");
+    println!(r"                      (e.g., 'taxi SWA123 24L via A B')");
+    println!(r"");
+    println!(r"  cross [id] [rw]   - Clears a taxiing plane across a runway on its route.");
+    println!(r"                      (e.g., 'cross SWA123 24L')");
+    println!(r"");
+    println!(r"  gate [id] [gate]  - Assigns an arrival its gate, sized for its type.");
+    println!(r"                      (e.g., 'gate DAL456 A1')");
+    println!(r"");
+    println!(r"  lineup [id] [rw]  - Lines a 'Ready for Takeoff' plane up on the runway.");
+    println!(r"                      (e.g., 'lineup SWA123 24L')");
+    println!(r"");
     println!(r"  takeoff [id] [rw] - Clears a 'Ready for Takeoff' plane to depart.");
     println!(r"                      (e.g., 'takeoff SWA123 24L')");
     println!(r"");
     println!(r"  land [id] [rw]    - Clears a 'Ready to Land' plane to arrive.");
     println!(r"                      (e.g., 'land DAL456 18')");
     println!(r"");
+    println!(r"  goaround [id]     - Sends a plane on final around to try again.");
+    println!(r"                      (e.g., 'goaround DAL456')");
+    println!(r"");
+    println!(r"  alert [id]        - Calls out fire and rescue or paramedics");
+    println!(r"                      for a plane that declared an emergency.");
+    println!(r"                      (e.g., 'alert DAL456')");
+    println!(r"");
+    println!(r"  heading [id] [hdg] - Turns an airborne plane onto a heading.");
+    println!(r"                      (e.g., 'heading DAL456 270')");
+    println!(r"");
+    println!(r"  climb/descend [id] [alt] - Assigns an altitude (feet or FL).");
+    println!(r"                      (e.g., 'descend DAL456 5000')");
+    println!(r"");
+    println!(r"  speed [id] [kt]   - Assigns a speed within the type's limits.");
+    println!(r"                      (e.g., 'speed DAL456 210')");
+    println!(r"");
+    println!(r"  direct [id] [fix] - Sends a plane direct to a fix on the radar.");
+    println!(r"                      (e.g., 'direct DAL456 BRAVO')");
+    println!(r"");
+    println!(r"  hold [id] [fix]   - Holds a plane at a fix's published hold.");
+    println!(r"                      (e.g., 'hold DAL456 BRAVO')");
+    println!(r"");
+    println!(r"  sequence [id] after [id] - Reorders the arrival sequence.");
+    println!(r"                      (e.g., 'sequence DAL456 after UAL77')");
+    println!(r"");
     println!(r"  wait              - Does nothing and advances the game clock.");
     println!(r"                      (Useful if no actions are needed)");
     println!(r"");
@@ -110,15 +152,25 @@ fn show_how_to_play() {
     println!(r"");
     println!(r"SCORING:");
     println!(r"  +10 points - Successful Takeoff or Landing.");
-    println!(r"  +5 points  - Successful Pushback.");
+    println!(r"  +5 points  - Successful Pushback, or alerting emergency services.");
+    println!(r"  +25 points - Landing an emergency at once; 1 less per tick it waits.");
     println!(r"  -5 points  - Any penalty.");
     println!(r"");
     println!(r"PENALTIES:");
     println!(r"  - Giving a command to a plane that isn't ready.");
     println!(r"  - Clearing a plane for pushback while it's still boarding.");
     println!(r"  - Using a runway that is CLOSED due to weather.");
+    println!(r"  - Landing another plane while an emergency waits to land.");
+    println!(r"  - Landing an emergency before alerting the services it needs.");
+    println!(r"  - Landing a plane ahead of its turn in the arrival sequence.");
     println!(r"  - Getting 3 penalties in a row ends the game.");
     println!(r"");
+    println!(r"FUEL:");
+    println!(r"  Arrivals burn fuel every minute they are airborne. Below 35");
+    println!(r"  minutes they declare MINIMUM FUEL, and below their 30-minute");
+    println!(r"  final reserve a MAYDAY; both are listed first on the radar.");
+    println!(r"  Running a plane dry ends the game.");
+    println!(r"");
     println!(r"-----------------------------------------------");
     println!(r"");
     print!("Press [Enter] to return to the main menu...");
@@ -126,9 +178,27 @@ fn show_how_to_play() {
     get_user_input(); // Wait for user to press Enter
 }
 
+/// Prints every event the engine produced since the last call.
+fn print_events(game: &mut game::Game) {
+    for event in game.drain_events() {
+        match event {
+            SimEvent::GameOver {
+                final_score,
+                reason,
+            } => {
+                println!("\n******************************************");
+                println!("{}", reason);
+                println!("FINAL SCORE: {}", final_score);
+                println!("******************************************");
+            }
+            event => println!("{}", event),
+        }
+    }
+}
+
 /// The main game loop where the simulation runs.
-fn run_game() {
-    let mut game = game::Game::new();
+fn run_game(options: &Options) {
+    let mut game = new_game(options);
     clear_screen();
     println!(r"=========================================================================+*=====================+++=");
     println!(r"============================================++++=*+*++====+===++==++====+%%***++=++===+**++*++*####*");
@@ -518,15 +588,18 @@ fn run_game() {
         if input.eq_ignore_ascii_case("quit") {
             // Also check for "quit" inside the game loop
             game.process_command(input);
+            print_events(&mut game);
             break; // Exit the game loop
         }
 
         // Process the command
         game.process_command(input);
+        print_events(&mut game);
 
         // Update the game state (the "tick")
         // We only tick *after* a command is processed
         game.update();
+        print_events(&mut game);
 
         // Wait for the next tick *if* the game isn't over
         if !game.is_game_over() {
@@ -537,6 +610,10 @@ fn run_game() {
         }
     }
 
+    // Print the seed so the session can be reproduced later
+    println!("SESSION SEED: {}", game.seed());
+    save_replay(&game, options);
+
     // Game is over, wait for user to see the "fired" message
     if game.is_game_over() {
         println!("\nPress [Enter] to return to the main menu...");
@@ -546,16 +623,230 @@ fn run_game() {
     // If user just typed "quit", return to menu immediately
 }
 
+/// Options given on the command line.
+struct Options {
+    seed: Option<u64>,
+    /// Set by --headless or --script
+    headless: bool,
+    /// Script file to run; None reads the script from stdin
+    script: Option<String>,
+    /// Where to save a replay of the session
+    record: Option<String>,
+    /// Replay file to re-run and verify
+    replay: Option<String>,
+    /// Airport layout file; None uses the built-in KPDX
+    airport: Option<String>,
+    /// Aircraft performance file; None uses the built-in types
+    aircraft: Option<String>,
+    /// Set by --scheduler: show AMAN/DMAN advisories and the delay baseline
+    scheduler: bool,
+}
+
+/// Parses the command line, exiting with a usage message on bad input.
+fn parse_args() -> Options {
+    let usage = "usage: atc_simulator [--seed N] [--headless] [--script FILE] [--record FILE] [--airport FILE] [--aircraft FILE] [--scheduler] | --replay FILE [--airport FILE] [--aircraft FILE]";
+    let mut options = Options {
+        seed: None,
+        headless: false,
+        script: None,
+        record: None,
+        replay: None,
+        airport: None,
+        aircraft: None,
+        scheduler: false,
+    };
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => match args.next().and_then(|n| n.parse().ok()) {
+                Some(seed) => options.seed = Some(seed),
+                None => {
+                    eprintln!("--seed needs a number\n{}", usage);
+                    std::process::exit(2);
+                }
+            },
+            "--headless" => options.headless = true,
+            "--scheduler" => options.scheduler = true,
+            "--script" => match args.next() {
+                Some(path) => {
+                    options.script = Some(path);
+                    options.headless = true;
+                }
+                None => {
+                    eprintln!("--script needs a file\n{}", usage);
+                    std::process::exit(2);
+                }
+            },
+            "--record" | "--replay" | "--airport" | "--aircraft" => match args.next() {
+                Some(path) if arg == "--record" => options.record = Some(path),
+                Some(path) if arg == "--replay" => options.replay = Some(path),
+                Some(path) if arg == "--airport" => options.airport = Some(path),
+                Some(path) => options.aircraft = Some(path),
+                None => {
+                    eprintln!("{} needs a file\n{}", arg, usage);
+                    std::process::exit(2);
+                }
+            },
+            _ => {
+                eprintln!("unknown argument '{}'\n{}", arg, usage);
+                std::process::exit(2);
+            }
+        }
+    }
+    options
+}
+
+/// Loads the --airport layout (or the built-in one), exiting on a bad file
+/// or one `navdata` doesn't know.
+fn load_airport(options: &Options, navdata: &NavDatabase) -> Airport {
+    match &options.airport {
+        Some(path) => Airport::load(path, navdata).unwrap_or_else(|e| {
+            eprintln!("{}: {}", path, e);
+            std::process::exit(1);
+        }),
+        None => Airport::new(),
+    }
+}
+
+/// Loads the --aircraft table (or the built-in one), exiting on a bad file.
+fn load_aircraft(options: &Options) -> AircraftDatabase {
+    match &options.aircraft {
+        Some(path) => AircraftDatabase::load(path).unwrap_or_else(|e| {
+            eprintln!("{}: {}", path, e);
+            std::process::exit(1);
+        }),
+        None => AircraftDatabase::new(),
+    }
+}
+
+/// Starts a session with the seed, airport, aircraft and scheduler given
+/// on the command line.
+fn new_game(options: &Options) -> game::Game {
+    let seed = options.seed.unwrap_or_else(rand::random);
+    let navdata = NavDatabase::new();
+    let airport = load_airport(options, &navdata);
+    let mut game = game::Game::with_navdata(seed, airport, load_aircraft(options), navdata);
+    if options.scheduler {
+        game.enable_scheduler();
+    }
+    game
+}
+
+/// Runs a command script with no screen clearing or sleeping, then prints a
+/// summary. Used for regression tests and tutorial scenarios in CI.
+fn run_headless(options: &Options) {
+    let source = match &options.script {
+        Some(path) => std::fs::read_to_string(path),
+        None => {
+            let mut source = String::new();
+            io::stdin().read_to_string(&mut source).map(|_| source)
+        }
+    };
+    let source = source.unwrap_or_else(|e| {
+        eprintln!("could not read script: {}", e);
+        std::process::exit(1);
+    });
+    let script = Script::parse(&source).unwrap_or_else(|e| {
+        eprintln!("script error: {}", e);
+        std::process::exit(1);
+    });
+
+    let mut game = new_game(options);
+    script.run(&mut game, |tick, event| println!("[{:>4}] {}", tick, event));
+
+    println!("--- SUMMARY ---");
+    println!("SEED: {}", game.seed());
+    println!("TICKS: {}", game.tick());
+    println!("SCORE: {}", game.score);
+    println!("STRIKES: {}/3 (total penalties: {})", game.penalty_strikes, game.stats.penalties);
+    println!("INCIDENTS: {}", game.incidents);
+    let incursions: Vec<String> = IncursionSeverity::ALL
+        .iter()
+        .map(|severity| {
+            let count = game.incursions.iter().filter(|i| i.severity == *severity).count();
+            format!("{}: {}", severity, count)
+        })
+        .collect();
+    println!("RUNWAY INCURSIONS: {} ({})", game.incursions.len(), incursions.join(", "));
+    println!(
+        "MOVEMENTS: {} (takeoffs: {}, landings: {}, pushbacks: {})",
+        game.stats.movements(),
+        game.stats.takeoffs,
+        game.stats.landings,
+        game.stats.pushbacks
+    );
+    println!("GO-AROUNDS: {}", game.stats.go_arounds);
+    println!("EMERGENCIES: {}", game.stats.emergencies);
+    if let Some(scheduler) = &game.scheduler {
+        let report = scheduler.report;
+        println!(
+            "DELAY: {} ticks over {} movements (first-fit scheduler baseline: {})",
+            report.actual_ticks, report.movements, report.planned_ticks
+        );
+    }
+    save_replay(&game, options);
+}
+
+/// Writes the session to the --record file, if one was given.
+fn save_replay(game: &game::Game, options: &Options) {
+    if let Some(path) = &options.record {
+        match std::fs::write(path, Replay::record(game).to_jsonl()) {
+            Ok(()) => println!("Replay saved to {}", path),
+            Err(e) => eprintln!("could not save replay to {}: {}", path, e),
+        }
+    }
+}
+
+/// Re-runs a recorded session and checks it ends on the recorded score.
+fn run_replay(path: &str, options: &Options) {
+    let replay = std::fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|source| Replay::from_jsonl(&source).map_err(|e| e.to_string()))
+        .unwrap_or_else(|e| {
+            eprintln!("could not load replay {}: {}", path, e);
+            std::process::exit(1);
+        });
+
+    let airport = load_airport(options, &NavDatabase::new());
+    let outcome = replay.run(airport, load_aircraft(options)).unwrap_or_else(|e| {
+        eprintln!("could not replay {}: {}", path, e);
+        std::process::exit(1);
+    });
+    println!("SEED: {}", replay.seed);
+    println!("INPUTS: {}", replay.inputs.len());
+    println!("TICKS: {}", outcome.replayed_tick);
+    if outcome.matches() {
+        println!("REPLAY OK: final score {} matches", outcome.replayed_score);
+    } else {
+        println!(
+            "REPLAY MISMATCH: recorded score {}, replayed score {}",
+            outcome.recorded_score, outcome.replayed_score
+        );
+        std::process::exit(1);
+    }
+}
+
 // --- MAIN FUNCTION ---
 // Entry point, contains the application menu loop
 fn main() {
+    let options = parse_args();
+    if let Some(path) = &options.replay {
+        run_replay(path, &options);
+        return;
+    }
+    if options.headless {
+        run_headless(&options);
+        return;
+    }
+
     loop {
         let choice = show_main_menu();
 
         match choice.as_str() {
             "1" => {
                 // Start the game
-                run_game();
+                run_game(&options);
             }
             "2" => {
                 // Show "How to Play"
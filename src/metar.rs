@@ -0,0 +1,659 @@
+use crate::airport::{Weather, Wind};
+use std::fmt;
+
+// --- METAR MODULE ---
+// Writes the airport weather as a METAR observation and reads METARs back,
+// so scenario authors can paste real reports to set conditions. The weather
+// forecast is written as a TAF.
+//   KPDX 181853Z 24015G25KT 3SM RA BKN008 12/10 A2992
+
+/// Simulation time at tick 0: the 18th, 1800Z. One tick is one minute.
+const START_DAY: u32 = 18;
+const START_MINUTE_OF_DAY: u32 = 18 * 60;
+
+/// Day of month and UTC time of an observation
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct ObservationTime {
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+}
+
+impl ObservationTime {
+    pub fn at_tick(tick: u64) -> Self {
+        let minutes = START_MINUTE_OF_DAY as u64 + tick;
+        let days = minutes / (24 * 60);
+        let minute_of_day = (minutes % (24 * 60)) as u32;
+        ObservationTime {
+            // Keep to days every month has
+            day: ((START_DAY - 1) as u64 + days) as u32 % 28 + 1,
+            hour: minute_of_day / 60,
+            minute: minute_of_day % 60,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum CloudCover {
+    Few,
+    Scattered,
+    Broken,
+    Overcast,
+    /// Sky obscured; the height is the vertical visibility
+    VerticalVisibility,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct CloudLayer {
+    pub cover: CloudCover,
+    pub height_ft: u32,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Metar {
+    pub station: String,
+    pub time: ObservationTime,
+    pub wind: Wind,
+    pub visibility_sm: f64,
+    /// Present weather groups as written, e.g. "-RA", "BR", "+TSRA"
+    pub phenomena: Vec<String>,
+    pub clouds: Vec<CloudLayer>,
+    pub temperature_c: Option<i32>,
+    pub dewpoint_c: Option<i32>,
+    pub altimeter_inhg: Option<f64>,
+}
+
+/// Why a METAR could not be read
+#[derive(Debug, PartialEq, Clone)]
+pub enum MetarError {
+    Empty,
+    BadStation(String),
+    BadTime(String),
+    BadWind(String),
+    MissingWind,
+    MissingVisibility,
+    UnexpectedGroup(String),
+}
+
+const DESCRIPTORS: [&str; 8] = ["MI", "BC", "PR", "DR", "BL", "SH", "TS", "FZ"];
+const PHENOMENA: [&str; 22] = [
+    "DZ", "RA", "SN", "SG", "IC", "PL", "GR", "GS", "UP", "BR", "FG", "FU", "VA", "DU", "SA",
+    "HZ", "PY", "PO", "SQ", "FC", "SS", "DS",
+];
+
+impl Metar {
+    /// Describe `weather` at `station` as a METAR
+    pub fn from_weather(station: &str, time: ObservationTime, weather: &Weather) -> Metar {
+        let mut phenomena = Vec::new();
+        if weather.rain {
+            phenomena.push(if weather.visibility_sm < 1.0 { "+RA" } else { "RA" }.to_string());
+        } else if weather.visibility_sm < 0.625 {
+            phenomena.push("FG".to_string());
+        } else if weather.visibility_sm <= 6.0 {
+            phenomena.push("BR".to_string());
+        }
+
+        let clouds = weather
+            .ceiling_ft
+            .map(|height_ft| CloudLayer {
+                cover: CloudCover::Broken,
+                height_ft,
+            })
+            .into_iter()
+            .collect();
+
+        // Wind direction is reported to the nearest ten degrees
+        let mut wind = weather.wind;
+        wind.direction = wind.direction.map(|d| ((d + 5) / 10 * 10).clamp(10, 360));
+
+        Metar {
+            station: station.to_string(),
+            time,
+            wind,
+            visibility_sm: weather.visibility_sm,
+            phenomena,
+            clouds,
+            temperature_c: None,
+            dewpoint_c: None,
+            altimeter_inhg: None,
+        }
+    }
+
+    /// The engine's view of this report
+    pub fn weather(&self) -> Weather {
+        let ceiling_ft = self
+            .clouds
+            .iter()
+            .filter(|layer| {
+                matches!(
+                    layer.cover,
+                    CloudCover::Broken | CloudCover::Overcast | CloudCover::VerticalVisibility
+                )
+            })
+            .map(|layer| layer.height_ft)
+            .min();
+        let rain = self
+            .phenomena
+            .iter()
+            .any(|group| group.contains("RA") || group.contains("DZ"));
+
+        Weather {
+            wind: self.wind,
+            rain,
+            visibility_sm: self.visibility_sm,
+            ceiling_ft,
+        }
+    }
+
+    /// Parse a METAR body. A leading "METAR" or "SPECI", AUTO/COR, variable
+    /// wind ranges and anything after RMK are accepted and ignored.
+    pub fn parse(text: &str) -> Result<Metar, MetarError> {
+        let mut groups = text
+            .split_whitespace()
+            .take_while(|group| *group != "RMK")
+            .skip_while(|group| *group == "METAR" || *group == "SPECI")
+            .peekable();
+
+        let station = groups.next().ok_or(MetarError::Empty)?;
+        if station.len() != 4 || !station.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(MetarError::BadStation(station.to_string()));
+        }
+        let time = groups.next().ok_or(MetarError::Empty)?;
+        let time = parse_time(time).ok_or_else(|| MetarError::BadTime(time.to_string()))?;
+
+        if let Some(&"AUTO" | &"COR") = groups.peek() {
+            groups.next();
+        }
+
+        let wind = groups.next().ok_or(MetarError::MissingWind)?;
+        let wind = parse_wind(wind).ok_or_else(|| MetarError::BadWind(wind.to_string()))?;
+
+        let mut metar = Metar {
+            station: station.to_uppercase(),
+            time,
+            wind,
+            visibility_sm: 0.0,
+            phenomena: Vec::new(),
+            clouds: Vec::new(),
+            temperature_c: None,
+            dewpoint_c: None,
+            altimeter_inhg: None,
+        };
+        let mut visibility = None;
+
+        while let Some(group) = groups.next() {
+            if is_variable_wind(group) {
+                continue;
+            }
+            if group == "CAVOK" {
+                visibility = Some(10.0);
+                continue;
+            }
+            if visibility.is_none() {
+                // "1 1/2SM" is split over two groups
+                if let Ok(whole) = group.parse::<u32>()
+                    && group.len() == 1
+                    && let Some(fraction) = groups.peek().and_then(|g| parse_visibility(g))
+                {
+                    groups.next();
+                    visibility = Some(whole as f64 + fraction);
+                    continue;
+                }
+                if let Some(sm) = parse_visibility(group) {
+                    visibility = Some(sm);
+                    continue;
+                }
+            }
+            if is_phenomenon(group) {
+                metar.phenomena.push(group.to_string());
+            } else if let Some(layer) = parse_cloud(group) {
+                metar.clouds.push(layer);
+            } else if matches!(group, "SKC" | "CLR" | "NSC" | "NCD") {
+                // No cloud to report
+            } else if let Some((temperature, dewpoint)) = parse_temperatures(group) {
+                metar.temperature_c = Some(temperature);
+                metar.dewpoint_c = dewpoint;
+            } else if let Some(altimeter) = parse_altimeter(group) {
+                metar.altimeter_inhg = Some(altimeter);
+            } else {
+                return Err(MetarError::UnexpectedGroup(group.to_string()));
+            }
+        }
+
+        metar.visibility_sm = visibility.ok_or(MetarError::MissingVisibility)?;
+        Ok(metar)
+    }
+}
+
+/// "181853Z"
+fn parse_time(group: &str) -> Option<ObservationTime> {
+    let digits = group.strip_suffix('Z')?;
+    if digits.len() != 6 || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let time = ObservationTime {
+        day: digits[0..2].parse().ok()?,
+        hour: digits[2..4].parse().ok()?,
+        minute: digits[4..6].parse().ok()?,
+    };
+    let valid = (1..=31).contains(&time.day) && time.hour < 24 && time.minute < 60;
+    valid.then_some(time)
+}
+
+/// "24015G25KT", "VRB03KT", "00000KT", "27008MPS"
+fn parse_wind(group: &str) -> Option<Wind> {
+    let (body, to_knots) = if let Some(body) = group.strip_suffix("KT") {
+        (body, 1.0)
+    } else {
+        (group.strip_suffix("MPS")?, 1.944)
+    };
+    // Slicing below is by byte, so anything but ASCII is no wind group
+    if body.len() < 5 || !body.is_ascii() {
+        return None;
+    }
+
+    let direction = match &body[0..3] {
+        "VRB" => None,
+        digits => Some(digits.parse::<u16>().ok().filter(|d| *d <= 360)?),
+    };
+    let (speed, gust) = match body[3..].split_once('G') {
+        Some((speed, gust)) => (speed, Some(gust)),
+        None => (&body[3..], None),
+    };
+    let knots = |s: &str| -> Option<u16> {
+        if s.is_empty() || !s.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        Some((s.parse::<f64>().ok()? * to_knots).round() as u16)
+    };
+
+    let speed_kt = knots(speed)?;
+    let gust_kt = match gust {
+        Some(gust) => Some(knots(gust)?),
+        None => None,
+    };
+    // A calm wind has no direction; a wind blowing from the north is 360
+    let direction = match direction {
+        _ if speed_kt == 0 => None,
+        Some(0) => return None,
+        direction => direction,
+    };
+    Some(Wind {
+        direction,
+        speed_kt,
+        gust_kt,
+    })
+}
+
+/// "210V270"
+fn is_variable_wind(group: &str) -> bool {
+    let bytes = group.as_bytes();
+    group.len() == 7
+        && bytes[3] == b'V'
+        && group[0..3].chars().all(|c| c.is_ascii_digit())
+        && group[4..7].chars().all(|c| c.is_ascii_digit())
+}
+
+/// "10SM", "3SM", "1/2SM", "M1/4SM", "P6SM", or four-digit metres ("9999")
+fn parse_visibility(group: &str) -> Option<f64> {
+    if group.len() == 4 && group.chars().all(|c| c.is_ascii_digit()) {
+        let metres: f64 = group.parse().ok()?;
+        return Some(if metres >= 9999.0 { 10.0 } else { metres / 1609.34 });
+    }
+
+    // Plain digits only: f64 parsing would also take "NaN", "inf" and "-1"
+    let number = |s: &str| -> Option<f64> {
+        if s.is_empty() || !s.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        s.parse().ok()
+    };
+    let miles = group.strip_suffix("SM")?;
+    if let Some(rest) = miles.strip_prefix('P') {
+        return number(rest).map(|_| 10.0);
+    }
+    let (less_than, miles) = match miles.strip_prefix('M') {
+        Some(rest) => (true, rest),
+        None => (false, miles),
+    };
+    let value = match miles.split_once('/') {
+        Some((numerator, denominator)) => {
+            let denominator = number(denominator).filter(|d| *d > 0.0)?;
+            number(numerator)? / denominator
+        }
+        None => number(miles)?,
+    };
+    let value = if less_than { value / 2.0 } else { value };
+    value.is_finite().then_some(value)
+}
+
+/// "-RA", "+TSRA", "VCSH", "BR", "FZFG"
+fn is_phenomenon(group: &str) -> bool {
+    let codes = group
+        .strip_prefix("VC")
+        .or_else(|| group.strip_prefix(['+', '-']))
+        .unwrap_or(group);
+    if codes.is_empty() || !codes.len().is_multiple_of(2) || !codes.is_ascii() {
+        return false;
+    }
+    (0..codes.len())
+        .step_by(2)
+        .map(|i| &codes[i..i + 2])
+        .all(|code| DESCRIPTORS.contains(&code) || PHENOMENA.contains(&code))
+}
+
+/// "BKN008", "OVC015CB", "VV002"
+fn parse_cloud(group: &str) -> Option<CloudLayer> {
+    let (cover, rest) = [
+        ("FEW", CloudCover::Few),
+        ("SCT", CloudCover::Scattered),
+        ("BKN", CloudCover::Broken),
+        ("OVC", CloudCover::Overcast),
+        ("VV", CloudCover::VerticalVisibility),
+    ]
+    .into_iter()
+    .find_map(|(prefix, cover)| group.strip_prefix(prefix).map(|rest| (cover, rest)))?;
+
+    let height = rest.get(0..3)?;
+    let suffix = &rest[3..];
+    if !height.chars().all(|c| c.is_ascii_digit()) || !matches!(suffix, "" | "CB" | "TCU" | "///") {
+        return None;
+    }
+    Some(CloudLayer {
+        cover,
+        height_ft: height.parse::<u32>().ok()? * 100,
+    })
+}
+
+/// "12/10", "M02/M05", "05/"
+fn parse_temperatures(group: &str) -> Option<(i32, Option<i32>)> {
+    let (temperature, dewpoint) = group.split_once('/')?;
+    let celsius = |s: &str| -> Option<i32> {
+        let (negative, digits) = match s.strip_prefix('M') {
+            Some(digits) => (true, digits),
+            None => (false, s),
+        };
+        if digits.len() != 2 || !digits.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let value: i32 = digits.parse().ok()?;
+        Some(if negative { -value } else { value })
+    };
+    let dewpoint = if dewpoint.is_empty() {
+        None
+    } else {
+        Some(celsius(dewpoint)?)
+    };
+    Some((celsius(temperature)?, dewpoint))
+}
+
+/// "A2992" (inches of mercury) or "Q1013" (hectopascals)
+fn parse_altimeter(group: &str) -> Option<f64> {
+    let digits = group.get(1..)?;
+    if digits.len() != 4 || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let value: f64 = digits.parse().ok()?;
+    match &group[0..1] {
+        "A" => Some(value / 100.0),
+        "Q" => Some(value * 0.02953),
+        _ => None,
+    }
+}
+
+fn format_visibility(sm: f64) -> String {
+    if sm >= 10.0 {
+        return "10SM".to_string();
+    }
+    let quarters = (sm * 4.0).round() as u32;
+    let (whole, fraction) = (quarters / 4, quarters % 4);
+    let fraction = match fraction {
+        1 => "1/4",
+        2 => "1/2",
+        3 => "3/4",
+        _ => "",
+    };
+    match (whole, fraction) {
+        (0, "") => "M1/4SM".to_string(),
+        (0, fraction) => format!("{}SM", fraction),
+        (whole, "") => format!("{}SM", whole),
+        (whole, fraction) => format!("{} {}SM", whole, fraction),
+    }
+}
+
+impl fmt::Display for ObservationTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:02}{:02}{:02}Z", self.day, self.hour, self.minute)
+    }
+}
+
+impl Metar {
+    /// Wind, visibility, weather and cloud groups. TAFs cap visibility at
+    /// "P6SM" where METARs report up to 10SM.
+    fn write_conditions(&self, f: &mut fmt::Formatter, taf: bool) -> fmt::Result {
+        let wind = &self.wind;
+        if wind.speed_kt == 0 {
+            write!(f, "00000KT")?;
+        } else {
+            match wind.direction {
+                Some(direction) => write!(f, "{:03}{:02}", direction, wind.speed_kt)?,
+                None => write!(f, "VRB{:02}", wind.speed_kt)?,
+            }
+            if let Some(gust) = wind.gust_kt {
+                write!(f, "G{:02}", gust)?;
+            }
+            write!(f, "KT")?;
+        }
+
+        if taf && self.visibility_sm > 6.0 {
+            write!(f, " P6SM")?;
+        } else {
+            write!(f, " {}", format_visibility(self.visibility_sm))?;
+        }
+        for group in &self.phenomena {
+            write!(f, " {}", group)?;
+        }
+        if self.clouds.is_empty() {
+            write!(f, " SKC")?;
+        }
+        for layer in &self.clouds {
+            let cover = match layer.cover {
+                CloudCover::Few => "FEW",
+                CloudCover::Scattered => "SCT",
+                CloudCover::Broken => "BKN",
+                CloudCover::Overcast => "OVC",
+                CloudCover::VerticalVisibility => "VV",
+            };
+            write!(f, " {}{:03}", cover, layer.height_ft / 100)?;
+        }
+        Ok(())
+    }
+}
+
+/// A terminal aerodrome forecast: the conditions expected from each time
+#[derive(Debug, PartialEq, Clone)]
+pub struct Taf {
+    pub station: String,
+    pub issued: ObservationTime,
+    /// Start of each forecast period and the weather expected from then on.
+    /// The first period starts when the forecast is issued.
+    pub periods: Vec<(ObservationTime, Weather)>,
+}
+
+impl fmt::Display for Taf {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Valid for 24 hours from the hour of issue
+        let issued = &self.issued;
+        write!(
+            f,
+            "TAF {} {} {:02}{:02}/{:02}{:02}",
+            self.station,
+            issued,
+            issued.day,
+            issued.hour,
+            issued.day % 28 + 1,
+            issued.hour
+        )?;
+
+        for (index, (time, weather)) in self.periods.iter().enumerate() {
+            let metar = Metar::from_weather(&self.station, *time, weather);
+            if index == 0 {
+                write!(f, " ")?;
+            } else {
+                write!(f, "\n     FM{:02}{:02}{:02} ", time.day, time.hour, time.minute)?;
+            }
+            metar.write_conditions(f, true)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Metar {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} ", self.station, self.time)?;
+        self.write_conditions(f, false)?;
+
+        if let Some(temperature) = self.temperature_c {
+            let celsius = |c: i32| {
+                if c < 0 {
+                    format!("M{:02}", -c)
+                } else {
+                    format!("{:02}", c)
+                }
+            };
+            write!(f, " {}/", celsius(temperature))?;
+            if let Some(dewpoint) = self.dewpoint_c {
+                write!(f, "{}", celsius(dewpoint))?;
+            }
+        }
+        if let Some(altimeter) = self.altimeter_inhg {
+            write!(f, " A{:04}", (altimeter * 100.0).round() as u32)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for MetarError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MetarError::Empty => write!(f, "METAR is empty or cut short"),
+            MetarError::BadStation(group) => write!(f, "'{}' is not an ICAO station code", group),
+            MetarError::BadTime(group) => write!(f, "'{}' is not an observation time (DDHHMMZ)", group),
+            MetarError::BadWind(group) => write!(f, "'{}' is not a wind group (e.g. 24015G25KT)", group),
+            MetarError::MissingWind => write!(f, "METAR has no wind group"),
+            MetarError::MissingVisibility => write!(f, "METAR has no visibility"),
+            MetarError::UnexpectedGroup(group) => write!(f, "don't know what '{}' means", group),
+        }
+    }
+}
+
+impl std::error::Error for MetarError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_full_report() {
+        let metar = Metar::parse("METAR KPDX 181853Z 24015G25KT 210V270 1 1/2SM -RA BR BKN008 OVC020 12/11 A2992 RMK AO2")
+            .unwrap();
+        assert_eq!(metar.station, "KPDX");
+        assert_eq!(
+            metar.time,
+            ObservationTime {
+                day: 18,
+                hour: 18,
+                minute: 53,
+            }
+        );
+        assert_eq!(
+            metar.wind,
+            Wind {
+                direction: Some(240),
+                speed_kt: 15,
+                gust_kt: Some(25),
+            }
+        );
+        assert_eq!(metar.visibility_sm, 1.5);
+        assert_eq!(metar.phenomena, vec!["-RA", "BR"]);
+        assert_eq!(metar.clouds.len(), 2);
+        assert_eq!(metar.temperature_c, Some(12));
+        assert_eq!(metar.dewpoint_c, Some(11));
+        assert_eq!(metar.altimeter_inhg, Some(29.92));
+
+        let weather = metar.weather();
+        assert!(weather.rain);
+        assert_eq!(weather.ceiling_ft, Some(800));
+    }
+
+    #[test]
+    fn tells_a_north_wind_from_a_variable_or_calm_one() {
+        let wind = |text: &str| Metar::parse(&format!("KPDX 181853Z {} 10SM CLR", text)).map(|m| m.wind.direction);
+        assert_eq!(wind("36012KT"), Ok(Some(360)));
+        assert_eq!(wind("VRB03KT"), Ok(None));
+        assert_eq!(wind("00000KT"), Ok(None));
+        assert_eq!(wind("00012KT"), Err(MetarError::BadWind("00012KT".to_string())));
+    }
+
+    #[test]
+    fn converts_metres_per_second_and_metres() {
+        let metar = Metar::parse("EGLL 181850Z 27008MPS 9999 SCT030").unwrap();
+        assert_eq!(metar.wind.speed_kt, 16);
+        assert_eq!(metar.visibility_sm, 10.0);
+    }
+
+    #[test]
+    fn round_trips_through_display() {
+        for text in [
+            "KPDX 181853Z 24015G25KT 3SM RA BKN008",
+            "KPDX 010000Z VRB03KT 1/2SM FG VV002",
+            "KPDX 181853Z 00000KT 10SM CLR",
+        ] {
+            let metar = Metar::parse(text).unwrap();
+            assert_eq!(Metar::parse(&metar.to_string()), Ok(metar), "{}", text);
+        }
+    }
+
+    #[test]
+    fn rejects_non_ascii_wind_without_panicking() {
+        assert_eq!(
+            Metar::parse("KPDX 181853Z 2é015KT 10SM"),
+            Err(MetarError::BadWind("2é015KT".to_string()))
+        );
+        assert_eq!(
+            Metar::parse("KPDX 181853Z ééééKT 10SM"),
+            Err(MetarError::BadWind("ééééKT".to_string()))
+        );
+    }
+
+    #[test]
+    fn rejects_a_zero_denominator_visibility() {
+        assert_eq!(
+            Metar::parse("KPDX 181853Z 24015KT 0/0SM"),
+            Err(MetarError::UnexpectedGroup("0/0SM".to_string()))
+        );
+        assert_eq!(
+            Metar::parse("KPDX 181853Z 24015KT 1 0/0SM"),
+            Err(MetarError::UnexpectedGroup("1".to_string()))
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_reports() {
+        assert_eq!(Metar::parse(""), Err(MetarError::Empty));
+        assert_eq!(Metar::parse("KPDX"), Err(MetarError::Empty));
+        assert_eq!(
+            Metar::parse("PDX 181853Z 24015KT 10SM"),
+            Err(MetarError::BadStation("PDX".to_string()))
+        );
+        assert_eq!(
+            Metar::parse("KPDX 182553Z 24015KT 10SM"),
+            Err(MetarError::BadTime("182553Z".to_string()))
+        );
+        assert_eq!(Metar::parse("KPDX 181853Z"), Err(MetarError::MissingWind));
+        assert_eq!(
+            Metar::parse("KPDX 181853Z 24015KT"),
+            Err(MetarError::MissingVisibility)
+        );
+    }
+}
@@ -0,0 +1,285 @@
+use crate::flight_plan::Waypoint;
+use serde::Deserialize;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fmt;
+
+// --- NAVDATA MODULE ---
+// Airports, navaids and airways with their real positions, and the route
+// search flight plans are filed with: the shortest great-circle path from
+// one airport to another along the airways. Loaded from data/navdata.toml.
+
+/// The navigation database used by every session
+const DEFAULT_NAVDATA: &str = include_str!("../data/navdata.toml");
+
+/// An airport flights can be planned to or from
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct NavAirport {
+    pub icao: String,
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Navaids departures leave the airways from and arrivals join them at
+    pub transitions: Vec<String>,
+}
+
+/// A radio navigation beacon airways are built on
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Navaid {
+    pub ident: String,
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+/// A published route between navaids, flown in either direction
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Airway {
+    pub name: String,
+    /// Navaids along the airway, in order
+    pub navaids: Vec<String>,
+}
+
+/// Every airport, navaid and airway in the database
+#[derive(Debug, Clone, Deserialize)]
+pub struct NavDatabase {
+    airports: Vec<NavAirport>,
+    navaids: Vec<Navaid>,
+    airways: Vec<Airway>,
+}
+
+/// Why a navigation database could not be loaded
+#[derive(Debug)]
+pub enum NavDataError {
+    Parse(toml::de::Error),
+    Invalid(String),
+}
+
+impl Default for NavDatabase {
+    fn default() -> Self {
+        NavDatabase::new()
+    }
+}
+
+impl NavDatabase {
+    /// The built-in database
+    pub fn new() -> Self {
+        NavDatabase::from_toml(DEFAULT_NAVDATA).expect("built-in navigation database is valid")
+    }
+
+    pub fn from_toml(source: &str) -> Result<NavDatabase, NavDataError> {
+        let database: NavDatabase = toml::from_str(source).map_err(NavDataError::Parse)?;
+
+        let idents: Vec<&str> = database
+            .airports
+            .iter()
+            .map(|a| a.icao.as_str())
+            .chain(database.navaids.iter().map(|n| n.ident.as_str()))
+            .collect();
+        for (i, ident) in idents.iter().enumerate() {
+            if idents[..i].contains(ident) {
+                return Err(NavDataError::Invalid(format!("{} is listed twice", ident)));
+            }
+        }
+        let positions = database
+            .airports
+            .iter()
+            .map(|a| (&a.icao, a.latitude, a.longitude))
+            .chain(database.navaids.iter().map(|n| (&n.ident, n.latitude, n.longitude)));
+        for (ident, latitude, longitude) in positions {
+            if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+                return Err(NavDataError::Invalid(format!(
+                    "{} has no such position {}, {}",
+                    ident, latitude, longitude
+                )));
+            }
+        }
+        for airway in &database.airways {
+            if airway.navaids.len() < 2 {
+                return Err(NavDataError::Invalid(format!("airway {} needs at least two navaids", airway.name)));
+            }
+            if let Some(unknown) = airway.navaids.iter().find(|n| database.navaid(n).is_none()) {
+                return Err(NavDataError::Invalid(format!("airway {} uses unknown navaid {}", airway.name, unknown)));
+            }
+        }
+        for airport in &database.airports {
+            if let Some(unknown) = airport.transitions.iter().find(|n| database.navaid(n).is_none()) {
+                return Err(NavDataError::Invalid(format!(
+                    "airport {} has unknown transition {}",
+                    airport.icao, unknown
+                )));
+            }
+        }
+        Ok(database)
+    }
+
+    pub fn airport(&self, icao: &str) -> Option<&NavAirport> {
+        self.airports.iter().find(|a| a.icao.eq_ignore_ascii_case(icao))
+    }
+
+    pub fn navaid(&self, ident: &str) -> Option<&Navaid> {
+        self.navaids.iter().find(|n| n.ident.eq_ignore_ascii_case(ident))
+    }
+
+    /// ICAO codes of every airport, in file order
+    pub fn airports(&self) -> Vec<&str> {
+        self.airports.iter().map(|a| a.icao.as_str()).collect()
+    }
+
+    /// Length of the longest route into `home` from any other airport, in
+    /// nautical miles
+    pub fn longest_route_nm(&self, home: &str) -> f64 {
+        self.airports
+            .iter()
+            .filter_map(|airport| self.route(&airport.icao, home))
+            .map(|route| route.windows(2).map(|leg| leg[0].distance_nm(&leg[1])).sum())
+            .fold(0.0, f64::max)
+    }
+
+    /// The shortest route from one airport to another: out along a
+    /// transition, over the airways, and in along a transition. Airports
+    /// with no airway between them are flown direct. None if either airport
+    /// is not in the database.
+    pub fn route(&self, from: &str, to: &str) -> Option<Vec<Waypoint>> {
+        let origin = self.airport(from)?;
+        let destination = self.airport(to)?;
+        let start = origin.icao.as_str();
+        let goal = destination.icao.as_str();
+
+        let mut best: HashMap<&str, u64> = HashMap::new();
+        let mut previous: HashMap<&str, (&str, Option<&str>)> = HashMap::new();
+        let mut queue = BinaryHeap::new();
+        best.insert(start, 0);
+        queue.push(Reverse((0, start)));
+
+        while let Some(Reverse((cost, ident))) = queue.pop() {
+            if best.get(ident).is_some_and(|&known| known < cost) {
+                continue;
+            }
+            if ident == goal {
+                return Some(self.rebuild(&previous, goal));
+            }
+            for (next, airway) in self.neighbours(ident, goal) {
+                let (Some(here), Some(there)) = (self.waypoint(ident, None), self.waypoint(next, None)) else {
+                    continue;
+                };
+                // Tenths of a mile, so costs can be compared exactly
+                let next_cost = cost + (here.distance_nm(&there) * 10.0).round() as u64;
+                if best.get(next).is_none_or(|&known| next_cost < known) {
+                    best.insert(next, next_cost);
+                    previous.insert(next, (ident, airway));
+                    queue.push(Reverse((next_cost, next)));
+                }
+            }
+        }
+        let origin = self.waypoint(start, None)?;
+        let destination = self.waypoint(goal, None)?;
+        Some(vec![origin, destination])
+    }
+
+    /// Idents reachable in one leg from `ident`, with the airway flown to
+    /// each. Departures leave by their airport's transitions, and arrivals
+    /// reach `goal` by its transitions.
+    fn neighbours<'a>(&'a self, ident: &str, goal: &'a str) -> Vec<(&'a str, Option<&'a str>)> {
+        let mut neighbours = Vec::new();
+        if let Some(airport) = self.airport(ident) {
+            neighbours.extend(airport.transitions.iter().map(|n| (n.as_str(), None)));
+            return neighbours;
+        }
+        for airway in &self.airways {
+            for (at, navaid) in airway.navaids.iter().enumerate() {
+                if navaid != ident {
+                    continue;
+                }
+                let before = at.checked_sub(1).map(|i| &airway.navaids[i]);
+                let after = airway.navaids.get(at + 1);
+                for next in before.into_iter().chain(after) {
+                    neighbours.push((next.as_str(), Some(airway.name.as_str())));
+                }
+            }
+        }
+        if let Some(goal_airport) = self.airport(goal)
+            && goal_airport.transitions.iter().any(|n| n == ident)
+        {
+            neighbours.push((goal_airport.icao.as_str(), None));
+        }
+        neighbours
+    }
+
+    /// The waypoint for an airport or navaid, reached along `airway`
+    fn waypoint(&self, ident: &str, airway: Option<&str>) -> Option<Waypoint> {
+        let (name, latitude, longitude) = match self.airport(ident) {
+            Some(airport) => (&airport.icao, airport.latitude, airport.longitude),
+            None => {
+                let navaid = self.navaid(ident)?;
+                (&navaid.ident, navaid.latitude, navaid.longitude)
+            }
+        };
+        Some(Waypoint {
+            name: name.clone(),
+            latitude,
+            longitude,
+            airway: airway.map(str::to_string),
+        })
+    }
+
+    fn rebuild(&self, previous: &HashMap<&str, (&str, Option<&str>)>, goal: &str) -> Vec<Waypoint> {
+        let mut route = Vec::new();
+        let mut ident = goal;
+        while let Some(&(from, airway)) = previous.get(ident) {
+            route.extend(self.waypoint(ident, airway));
+            ident = from;
+        }
+        route.extend(self.waypoint(ident, None));
+        route.reverse();
+        route
+    }
+}
+
+impl fmt::Display for NavDataError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NavDataError::Parse(e) => write!(f, "invalid navigation database: {}", e),
+            NavDataError::Invalid(message) => write!(f, "invalid navigation database: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for NavDataError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn routes_over_the_airways_between_airports() {
+        let navdata = NavDatabase::new();
+        let route = navdata.route("kpdx", "KSEA").unwrap();
+        assert_eq!(route.first().map(|w| w.name.as_str()), Some("KPDX"));
+        assert_eq!(route.last().map(|w| w.name.as_str()), Some("KSEA"));
+        assert!(route.len() > 2);
+        assert!(route.iter().skip(1).any(|w| w.airway.is_some()));
+
+        // No shorter than flying direct
+        let flown: f64 = route.windows(2).map(|leg| leg[0].distance_nm(&leg[1])).sum();
+        assert!(flown >= route[0].distance_nm(&route[route.len() - 1]));
+    }
+
+    #[test]
+    fn has_no_route_to_an_unknown_airport() {
+        let navdata = NavDatabase::new();
+        assert!(navdata.route("KPDX", "KXXX").is_none());
+        assert!(navdata.route("KXXX", "KPDX").is_none());
+    }
+
+    #[test]
+    fn longest_route_is_at_least_every_route() {
+        let navdata = NavDatabase::new();
+        let longest = navdata.longest_route_nm("KPDX");
+        for origin in navdata.airports().into_iter().filter(|icao| *icao != "KPDX") {
+            let route = navdata.route(origin, "KPDX").unwrap();
+            let flown: f64 = route.windows(2).map(|leg| leg[0].distance_nm(&leg[1])).sum();
+            assert!(flown <= longest + 1e-9, "{}", origin);
+        }
+    }
+}
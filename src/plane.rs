@@ -1,18 +1,47 @@
+use crate::aircraft::{AircraftDatabase, AircraftType, WeightClass};
+use crate::airport::{Fix, GateSize, Hold, Runway, RunwayOperation, Turn, Weather};
+use crate::emergency::{Emergency, EmergencyError, EmergencyKind};
+use crate::event::{Clearance, PenaltyReason, SimEvent};
+use crate::flight::{self, FlightState, HoldLeg, HoldingPattern, Performance};
 use crate::flight_plan::FlightPlan;
+use crate::fuel::{self, FuelState};
+use crate::ground::{self, GroundLayout, GroundPosition};
+use crate::separation;
 use rand::Rng;
 
 // --- PLANE MODULE ---
 // Contains logic specific to individual planes
 
+/// Most tailwind any type may take off or land with, in knots
+pub const TAILWIND_LIMIT_KT: u16 = 10;
+
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum PlaneStatus {
     Boarding,
     ReadyForPushback,
+    /// Pushed back from the gate, waiting for a taxi clearance
+    ReadyToTaxi,
     TaxiingToRunway,
     ReadyForTakeoff,
+    /// On the runway, holding it until cleared for takeoff
+    LinedUpAndWaiting,
+    /// Climbing out after takeoff
+    Departing,
+    /// Left our airspace after departing; handed off to the next sector
+    HandedOff,
+    /// Inbound, flying toward the field
     InAir,
+    /// Circling near the field at approach altitude
     ReadyToLand,
+    /// Flying a published holding pattern over a fix, in the stack
+    Holding,
+    /// Cleared to land, on final approach
+    OnFinal,
+    /// Went around; flying the published missed approach
+    MissedApproach,
     TaxiingToGate,
+    /// Landed with no gate free; waiting on the ramp for one
+    HoldingForGate,
     AtGate,
 }
 
@@ -20,51 +49,445 @@ pub enum PlaneStatus {
 pub struct Plane {
     pub id: String,
     pub flight_plan: FlightPlan, // Holds the flight plan
+    /// Performance of the plane's type, from the aircraft table
+    pub aircraft: AircraftType,
     pub status: PlaneStatus,
     /// Simple timer to simulate time-based events (e.g., boarding time)
     pub timer: i32,
+    /// Position and motion while airborne
+    pub flight: Option<FlightState>,
+    /// Position and route while on the ground
+    pub ground: Option<GroundPosition>,
+    /// The gate the plane is boarding at or heading for
+    pub gate: Option<String>,
+    /// The runway the plane is cleared to land on, while on final
+    pub runway: Option<String>,
+    /// Fuel on board, in pounds
+    pub fuel_lb: f64,
+    /// The last fuel state the crew declared
+    pub fuel_state: FuelState,
+    /// The emergency the crew has declared, if any
+    pub emergency: Option<Emergency>,
+    /// Level an arrival left to its own descent levels off at, counted up
+    /// from the approach altitude in 1000 ft steps while others wait below
+    pub arrival_level: Option<u32>,
 }
 
 impl Plane {
-    // --- THIS IS THE CORRECTED FUNCTION ---
-    // It now takes a FlightPlan as an argument, not a String
-    pub fn new(flight_plan: FlightPlan) -> Self {
+    /// A new flight, boarding, flying `flight_plan` in a type from `aircraft`
+    pub fn new(flight_plan: FlightPlan, aircraft: &AircraftDatabase, rng: &mut impl Rng) -> Self {
+        let aircraft = aircraft.lookup(&flight_plan.aircraft_type);
         Plane {
             id: flight_plan.flight_id.clone(), // Get ID from the plan
+            fuel_lb: fuel::departure_fuel_lb(&flight_plan, &aircraft),
+            fuel_state: FuelState::Normal,
+            emergency: None,
+            aircraft,
             flight_plan,                       // Store the whole plan
             status: PlaneStatus::Boarding,
-            timer: rand::thread_rng().gen_range(10..=20), // 10-20 "ticks" to board
+            timer: rng.gen_range(10..=20), // 10-20 "ticks" to board
+            flight: None,
+            ground: None,
+            gate: None,
+            runway: None,
+            arrival_level: None,
         }
     }
 
     /// update is called on each "tick" of the game loop
-    pub fn update(&mut self) {
+    pub fn update(&mut self, layout: &GroundLayout, rng: &mut impl Rng, events: &mut Vec<SimEvent>) {
+        if let Some(position) = self.ground.as_mut() {
+            position.recent.clear();
+        }
+        if self.flight.is_some() {
+            return self.fly(events);
+        }
+        if matches!(self.status, PlaneStatus::TaxiingToRunway | PlaneStatus::TaxiingToGate)
+            && self.ground.is_some()
+        {
+            return self.taxi(layout, rng, events);
+        }
+
         if self.timer > 0 {
             self.timer -= 1;
             return;
         }
 
         // When timer hits 0, advance to the next state
-        match self.status {
-            PlaneStatus::Boarding => {
-                self.status = PlaneStatus::ReadyForPushback;
-                println!("INFO: {} is fully boarded and ready for pushback.", self.id);
+        let next = match self.status {
+            PlaneStatus::Boarding => PlaneStatus::ReadyForPushback,
+            PlaneStatus::TaxiingToRunway => PlaneStatus::ReadyForTakeoff,
+            PlaneStatus::TaxiingToGate => PlaneStatus::AtGate,
+            // Other states wait for user input
+            _ => return,
+        };
+        self.set_status(next, events);
+    }
+
+    /// Roll along the taxi route; at the end, hold short or park. A plane
+    /// reaching a runway it isn't cleared across stops and asks to cross,
+    /// and once in a while the pilot carries straight on.
+    fn taxi(&mut self, layout: &GroundLayout, rng: &mut impl Rng, events: &mut Vec<SimEvent>) {
+        let Some(position) = self.ground.as_mut() else {
+            return;
+        };
+        let was_holding = position.holding_for.is_some();
+        position.advance(layout, ground::TAXI_FT_PER_TICK);
+        if !was_holding && let Some(runway) = position.holding_for.clone() {
+            if rng.gen_bool(ground::DEVIATION_CHANCE) {
+                position.clear_to_cross(&runway);
+                position.deviation = Some(runway);
+            } else {
+                events.push(SimEvent::HoldingShort {
+                    plane: self.id.clone(),
+                    runway,
+                });
             }
-            PlaneStatus::TaxiingToRunway => {
-                self.status = PlaneStatus::ReadyForTakeoff;
-                println!("INFO: {} is at the runway, ready for takeoff.", self.id);
+        }
+        if !position.arrived() {
+            return;
+        }
+        let next = match self.status {
+            PlaneStatus::TaxiingToRunway => PlaneStatus::ReadyForTakeoff,
+            _ if self.gate.is_none() => PlaneStatus::HoldingForGate,
+            _ => PlaneStatus::AtGate,
+        };
+        self.set_status(next, events);
+    }
+
+    /// The runway this plane is holding short of, if any
+    pub fn holding_short<'a>(&self, layout: &'a GroundLayout) -> Option<&'a str> {
+        let position = self.ground.as_ref()?;
+        if !position.arrived() {
+            return None;
+        }
+        layout.node(&position.at)?.runway.as_deref()
+    }
+
+    /// Airborne: steer, integrate one tick of motion, then check whether
+    /// the plane has reached the next phase of its flight
+    fn fly(&mut self, events: &mut Vec<SimEvent>) {
+        let performance = self.performance();
+        let Some(flight) = self.flight.as_mut() else {
+            return;
+        };
+
+        if self.status == PlaneStatus::InAir {
+            // Head for the field, descending to its level and slowing down
+            // as it gets close, unless ATC said otherwise. Once there it
+            // circles, clear of the field, until it is down to approach
+            // altitude and asks to land.
+            if !flight.assigned.heading {
+                if flight.range_nm() <= flight::APPROACH_RANGE_NM {
+                    flight.orbiting = true;
+                } else {
+                    flight.target_heading = flight.bearing_to_field();
+                }
             }
-            PlaneStatus::InAir => {
-                // For this demo, planes in air will eventually want to land
-                self.status = PlaneStatus::ReadyToLand;
-                println!("INFO: {} (in air) is requesting to land.", self.id);
+            if !flight.assigned.altitude {
+                let level = f64::from(self.arrival_level.unwrap_or(0));
+                flight.target_altitude_ft = flight::APPROACH_ALTITUDE_FT + separation::VERTICAL_SEPARATION_FT * level;
             }
-            PlaneStatus::TaxiingToGate => {
-                self.status = PlaneStatus::AtGate;
-                println!("INFO: {} has arrived at the gate.", self.id);
+            if !flight.assigned.speed {
+                flight.target_speed_kt = if flight.range_nm() < 15.0 { 210.0 } else { 250.0 };
             }
-            // Other states wait for user input
-            _ => {}
+        } else if self.status == PlaneStatus::ReadyToLand && !flight.assigned.speed {
+            // Established, slowed to its approach speed
+            flight.target_speed_kt = f64::from(self.aircraft.approach_speed_kt);
+        } else if self.status == PlaneStatus::Departing
+            && !flight.assigned.heading
+            && flight.altitude_ft >= flight::DEPARTURE_TURN_ALTITUDE_FT
+            && let Some(course) = self.flight_plan.initial_course()
+        {
+            // Clear of the field: on course for the first fix of the route
+            flight.target_heading = course;
+        }
+        flight.advance_tick(&performance);
+        self.burn_fuel(events);
+        let Some(flight) = self.flight.as_mut() else {
+            return;
+        };
+
+        let next = match self.status {
+            PlaneStatus::InAir
+                if flight.range_nm() <= flight::APPROACH_RANGE_NM
+                    && flight.altitude_ft <= flight::APPROACH_ALTITUDE_FT + 100.0 =>
+            {
+                // Circle until cleared, unless being vectored
+                flight.orbiting = !flight.assigned.heading;
+                PlaneStatus::ReadyToLand
+            }
+            // Over the fix and at the missed approach altitude: back into
+            // the arrival sequence
+            PlaneStatus::MissedApproach
+                if flight.direct_to.is_none()
+                    && (flight.altitude_ft - flight.target_altitude_ft).abs() < 100.0 =>
+            {
+                flight.resume_own_navigation();
+                PlaneStatus::InAir
+            }
+            // Sent on out of the hold by a vector
+            PlaneStatus::Holding if flight.holding.is_none() => PlaneStatus::InAir,
+            PlaneStatus::Departing if flight.range_nm() > flight::AIRSPACE_RADIUS_NM => {
+                PlaneStatus::HandedOff
+            }
+            _ => return,
+        };
+        self.set_status(next, events);
+    }
+
+    /// Burn a tick's worth of fuel, and declare minimum or MAYDAY fuel the
+    /// first time the plane drops to each
+    fn burn_fuel(&mut self, events: &mut Vec<SimEvent>) {
+        let burn = self.aircraft.fuel_burn_lb_per_min * flight::SECONDS_PER_TICK / 60.0;
+        self.fuel_lb = (self.fuel_lb - burn).max(0.0);
+        let state = FuelState::for_endurance(self.endurance_minutes());
+        if state > self.fuel_state {
+            self.fuel_state = state;
+            events.push(SimEvent::FuelDeclared {
+                plane: self.id.clone(),
+                state,
+                minutes: self.endurance_minutes().floor() as u32,
+            });
         }
     }
+
+    /// Minutes of flying left in the tanks
+    pub fn endurance_minutes(&self) -> f64 {
+        self.fuel_lb / self.aircraft.fuel_burn_lb_per_min
+    }
+
+    fn set_status(&mut self, next: PlaneStatus, events: &mut Vec<SimEvent>) {
+        events.push(SimEvent::PlaneStateChanged {
+            plane: self.id.clone(),
+            from: self.status,
+            to: next,
+        });
+        self.status = next;
+    }
+
+    /// This plane's wake turbulence category
+    pub fn weight_class(&self) -> WeightClass {
+        self.aircraft.wake
+    }
+
+    pub fn gate_size(&self) -> GateSize {
+        self.aircraft.gate
+    }
+
+    /// Whether this is an inbound flight, in the air or taxiing in
+    pub fn is_arrival(&self) -> bool {
+        matches!(
+            self.status,
+            PlaneStatus::InAir
+                | PlaneStatus::ReadyToLand
+                | PlaneStatus::Holding
+                | PlaneStatus::OnFinal
+                | PlaneStatus::MissedApproach
+                | PlaneStatus::TaxiingToGate
+                | PlaneStatus::HoldingForGate
+        )
+    }
+
+    /// This plane's type performance, less what a failed engine takes away
+    pub fn performance(&self) -> Performance {
+        let mut performance = self.aircraft.performance();
+        if self.emergency.is_some_and(|e| e.kind == EmergencyKind::EngineFailure) {
+            performance.climb_fpm /= 2.0;
+            performance.max_speed_kt = performance.max_speed_kt.min(250).max(performance.min_speed_kt);
+        }
+        performance
+    }
+
+    /// Shortest runway this plane can use right now
+    pub fn required_runway_ft(&self) -> u32 {
+        let factor = self.emergency.map_or(1.0, |e| e.kind.runway_factor());
+        (f64::from(self.aircraft.runway_length_ft) * factor).round() as u32
+    }
+
+    /// Check that this plane can use `runway` at all in `weather`: long
+    /// enough, above the approach minimums, and within its wind limits.
+    /// Whether the runway is free is up to the caller.
+    pub fn check_runway_suits(
+        &self,
+        runway: &Runway,
+        weather: &Weather,
+        operation: RunwayOperation,
+    ) -> Result<(), PenaltyReason> {
+        let required_ft = self.required_runway_ft();
+        if runway.length_ft < required_ft {
+            return Err(PenaltyReason::RunwayTooShort {
+                plane: self.id.clone(),
+                runway: runway.id.clone(),
+                length_ft: runway.length_ft,
+                required_ft,
+            });
+        }
+        if operation == RunwayOperation::Landing && !weather.meets(runway.approach_minimums()) {
+            return Err(PenaltyReason::BelowMinimums {
+                plane: self.id.clone(),
+                runway: runway.id.clone(),
+                category: weather.category(),
+            });
+        }
+
+        let components = runway.wind_components(&weather.wind);
+        let crosswind_kt = components.crosswind.abs().round() as u16;
+        let limit_kt = self.aircraft.crosswind_limit_kt;
+        if crosswind_kt > limit_kt {
+            return Err(PenaltyReason::CrosswindExceeded {
+                plane: self.id.clone(),
+                runway: runway.id.clone(),
+                crosswind_kt,
+                limit_kt,
+            });
+        }
+        let tailwind_kt = components.tailwind().round() as u16;
+        if tailwind_kt > TAILWIND_LIMIT_KT {
+            return Err(PenaltyReason::TailwindExceeded {
+                plane: self.id.clone(),
+                runway: runway.id.clone(),
+                tailwind_kt,
+                limit_kt: TAILWIND_LIMIT_KT,
+            });
+        }
+        Ok(())
+    }
+
+    /// Whether this plane must be landed ahead of normal traffic: it has
+    /// declared an emergency, or MAYDAY fuel
+    pub fn has_priority(&self) -> bool {
+        self.emergency.is_some() || self.fuel_state == FuelState::Mayday
+    }
+
+    /// The crew declares `kind` at `tick`. A departure turns back for the
+    /// field and becomes an arrival.
+    pub fn declare_emergency(
+        &mut self,
+        kind: EmergencyKind,
+        tick: u64,
+        events: &mut Vec<SimEvent>,
+    ) -> Result<(), EmergencyError> {
+        if self.emergency.is_some() {
+            return Err(EmergencyError::AlreadyDeclared(self.id.clone()));
+        }
+        if self.flight.is_none() || !kind.can_happen(self.status) {
+            return Err(EmergencyError::WrongPhase {
+                plane: self.id.clone(),
+                kind,
+            });
+        }
+        self.emergency = Some(Emergency::new(kind, tick));
+        if self.status == PlaneStatus::Departing
+            && let Some(flight) = self.flight.as_mut()
+        {
+            flight.resume_own_navigation();
+            self.set_status(PlaneStatus::InAir, events);
+        }
+        Ok(())
+    }
+
+    /// Turn onto `heading`
+    pub fn assign_heading(&mut self, heading: u16) -> Result<Clearance, PenaltyReason> {
+        let flight = self.airborne()?;
+        let turn = flight::signed_turn(flight.heading, f64::from(heading));
+        flight.assign_heading(f64::from(heading));
+        Ok(Clearance::Heading {
+            heading,
+            right: turn >= 0.0,
+        })
+    }
+
+    /// Climb or descend to `altitude_ft`, if the type can get there
+    pub fn assign_altitude(&mut self, altitude_ft: u32) -> Result<Clearance, PenaltyReason> {
+        let ceiling_ft = self.performance().ceiling_ft;
+        if !(flight::MIN_VECTORING_ALTITUDE_FT..=ceiling_ft).contains(&altitude_ft) {
+            return Err(PenaltyReason::AltitudeOutOfRange {
+                plane: self.id.clone(),
+                altitude_ft,
+                floor_ft: flight::MIN_VECTORING_ALTITUDE_FT,
+                ceiling_ft,
+            });
+        }
+        let flight = self.airborne()?;
+        let climbing = f64::from(altitude_ft) > flight.altitude_ft;
+        flight.assign_altitude(f64::from(altitude_ft));
+        Ok(Clearance::Altitude {
+            altitude_ft,
+            climbing,
+        })
+    }
+
+    /// Fly `speed_kt`, if it is within the type's speed range
+    pub fn assign_speed(&mut self, speed_kt: u16) -> Result<Clearance, PenaltyReason> {
+        let Performance {
+            min_speed_kt,
+            max_speed_kt,
+            ..
+        } = self.performance();
+        if !(min_speed_kt..=max_speed_kt).contains(&speed_kt) {
+            return Err(PenaltyReason::SpeedOutOfRange {
+                plane: self.id.clone(),
+                speed_kt,
+                min_kt: min_speed_kt,
+                max_kt: max_speed_kt,
+            });
+        }
+        self.airborne()?.assign_speed(f64::from(speed_kt));
+        Ok(Clearance::Speed { speed_kt })
+    }
+
+    /// Proceed direct to `fix`
+    pub fn proceed_direct(&mut self, fix: &Fix) -> Result<Clearance, PenaltyReason> {
+        let (x_nm, y_nm) = fix.position();
+        self.airborne()?.proceed_direct(&fix.name, x_nm, y_nm);
+        Ok(Clearance::Direct {
+            fix: fix.name.clone(),
+        })
+    }
+
+    /// Enter the published hold at `fix`, at `level` of the stack. Only
+    /// arrivals that haven't been cleared to land can hold.
+    pub fn hold(
+        &mut self,
+        fix: &Fix,
+        hold: &Hold,
+        level: u32,
+        events: &mut Vec<SimEvent>,
+    ) -> Result<Clearance, PenaltyReason> {
+        if !matches!(
+            self.status,
+            PlaneStatus::InAir | PlaneStatus::ReadyToLand | PlaneStatus::MissedApproach | PlaneStatus::Holding
+        ) {
+            return Err(PenaltyReason::CannotHold(self.id.clone()));
+        }
+        let (x_nm, y_nm) = fix.position();
+        let altitude_ft = hold.altitude_ft(level);
+        self.airborne()?.enter_hold(
+            HoldingPattern {
+                fix: fix.name.clone(),
+                x_nm,
+                y_nm,
+                inbound_course: f64::from(hold.inbound_course),
+                right_turns: hold.turn == Turn::Right,
+                level,
+                leg: HoldLeg::Entry,
+            },
+            f64::from(altitude_ft),
+        );
+        if self.status != PlaneStatus::Holding {
+            self.set_status(PlaneStatus::Holding, events);
+        }
+        Ok(Clearance::Hold {
+            fix: fix.name.clone(),
+            altitude_ft,
+        })
+    }
+
+    /// Only planes in the air can be vectored
+    fn airborne(&mut self) -> Result<&mut FlightState, PenaltyReason> {
+        self.flight
+            .as_mut()
+            .ok_or_else(|| PenaltyReason::NotAirborne(self.id.clone()))
+    }
 }
@@ -0,0 +1,347 @@
+use crate::aircraft::AircraftDatabase;
+use crate::airport::Airport;
+use crate::emergency::EmergencyKind;
+use crate::game::Game;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// --- REPLAY MODULE ---
+// A replay is the session seed plus every input (command lines, METARs that
+// set the weather and scripted emergencies) and the tick it was given at. Because all randomness
+// comes from the seed, re-running the inputs at the same ticks reproduces the
+// session exactly.
+//
+// On disk a replay is JSON Lines: a header, one line per input, then the
+// end-of-session result used to verify the re-run.
+//   {"type":"header","seed":42,"airport":"Portland (classic)","aircraft":"9f3a61c04b2d8e17"}
+//   {"type":"metar","tick":0,"metar":"KPDX 181800Z 18010KT 2SM RA BKN006"}
+//   {"type":"command","tick":3,"command":"pushback SWA123"}
+//   {"type":"emergency","tick":9,"plane":"DAL456","kind":"engine_failure"}
+//   {"type":"end","tick":57,"score":25}
+
+/// Something given to the engine from outside, with the number of ticks
+/// simulated before it arrived
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordedInput {
+    /// A command line exactly as the controller typed it
+    Command { tick: u64, command: String },
+    /// A METAR that set the weather
+    Metar { tick: u64, metar: String },
+    /// An emergency a scenario declared
+    Emergency {
+        tick: u64,
+        plane: String,
+        kind: EmergencyKind,
+    },
+}
+
+impl RecordedInput {
+    pub fn tick(&self) -> u64 {
+        match self {
+            RecordedInput::Command { tick, .. }
+            | RecordedInput::Metar { tick, .. }
+            | RecordedInput::Emergency { tick, .. } => *tick,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ReplayLine {
+    Header {
+        seed: u64,
+        airport: String,
+        aircraft: String,
+    },
+    Command { tick: u64, command: String },
+    Metar { tick: u64, metar: String },
+    Emergency {
+        tick: u64,
+        plane: String,
+        kind: EmergencyKind,
+    },
+    End { tick: u64, score: i32 },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replay {
+    pub seed: u64,
+    /// Name of the airport layout the session was played at
+    pub airport: String,
+    /// Fingerprint of the aircraft table the session was flown with
+    pub aircraft: String,
+    pub inputs: Vec<RecordedInput>,
+    /// Tick the recorded session ended on
+    pub end_tick: u64,
+    /// Score the recorded session ended with
+    pub final_score: i32,
+}
+
+/// What re-running a replay produced, next to what was recorded
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReplayOutcome {
+    pub recorded_score: i32,
+    pub replayed_score: i32,
+    pub replayed_tick: u64,
+}
+
+impl ReplayOutcome {
+    pub fn matches(&self) -> bool {
+        self.recorded_score == self.replayed_score
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReplayError {
+    Malformed { line: usize, message: String },
+    MissingHeader,
+    MissingEnd,
+    /// The replay must be re-run at the layout it was recorded at
+    WrongAirport { recorded: String, given: String },
+    /// Nor with other aircraft types than it was recorded with
+    WrongAircraft { recorded: String, given: String },
+    BadMetar { metar: String, message: String },
+    /// A recorded emergency that could not be declared on the re-run
+    BadEmergency { plane: String, message: String },
+}
+
+impl Replay {
+    /// Capture a finished (or in-progress) session
+    pub fn record(game: &Game) -> Replay {
+        Replay {
+            seed: game.seed(),
+            airport: game.airport.name.clone(),
+            aircraft: game.aircraft.fingerprint(),
+            inputs: game.history().to_vec(),
+            end_tick: game.tick(),
+            final_score: game.score,
+        }
+    }
+
+    pub fn to_jsonl(&self) -> String {
+        let mut lines = vec![ReplayLine::Header {
+            seed: self.seed,
+            airport: self.airport.clone(),
+            aircraft: self.aircraft.clone(),
+        }];
+        lines.extend(self.inputs.iter().cloned().map(|input| match input {
+            RecordedInput::Command { tick, command } => ReplayLine::Command { tick, command },
+            RecordedInput::Metar { tick, metar } => ReplayLine::Metar { tick, metar },
+            RecordedInput::Emergency { tick, plane, kind } => ReplayLine::Emergency { tick, plane, kind },
+        }));
+        lines.push(ReplayLine::End {
+            tick: self.end_tick,
+            score: self.final_score,
+        });
+
+        let mut out = String::new();
+        for line in &lines {
+            // Serializing these plain structs cannot fail
+            out.push_str(&serde_json::to_string(line).unwrap());
+            out.push('\n');
+        }
+        out
+    }
+
+    pub fn from_jsonl(source: &str) -> Result<Replay, ReplayError> {
+        let mut header = None;
+        let mut end = None;
+        let mut inputs = Vec::new();
+
+        for (index, raw) in source.lines().enumerate() {
+            if raw.trim().is_empty() {
+                continue;
+            }
+            let line = serde_json::from_str(raw).map_err(|e| ReplayError::Malformed {
+                line: index + 1,
+                message: e.to_string(),
+            })?;
+            match line {
+                ReplayLine::Header { seed, airport, aircraft } => header = Some((seed, airport, aircraft)),
+                ReplayLine::Command { tick, command } => {
+                    inputs.push(RecordedInput::Command { tick, command })
+                }
+                ReplayLine::Metar { tick, metar } => inputs.push(RecordedInput::Metar { tick, metar }),
+                ReplayLine::Emergency { tick, plane, kind } => {
+                    inputs.push(RecordedInput::Emergency { tick, plane, kind })
+                }
+                ReplayLine::End { tick, score } => end = Some((tick, score)),
+            }
+        }
+
+        let (seed, airport, aircraft) = header.ok_or(ReplayError::MissingHeader)?;
+        let (end_tick, final_score) = end.ok_or(ReplayError::MissingEnd)?;
+        Ok(Replay {
+            seed,
+            airport,
+            aircraft,
+            inputs,
+            end_tick,
+            final_score,
+        })
+    }
+
+    /// Re-execute the session at `airport`, flying the types in `aircraft`,
+    /// through Game::process_command and Game::update, and report whether it
+    /// ends on the recorded score
+    pub fn run(&self, airport: Airport, aircraft: AircraftDatabase) -> Result<ReplayOutcome, ReplayError> {
+        if airport.name != self.airport {
+            return Err(ReplayError::WrongAirport {
+                recorded: self.airport.clone(),
+                given: airport.name,
+            });
+        }
+        if aircraft.fingerprint() != self.aircraft {
+            return Err(ReplayError::WrongAircraft {
+                recorded: self.aircraft.clone(),
+                given: aircraft.fingerprint(),
+            });
+        }
+        let mut game = Game::with_aircraft(self.seed, airport, aircraft);
+
+        for input in &self.inputs {
+            advance_to(&mut game, input.tick());
+            match input {
+                RecordedInput::Command { command, .. } => game.process_command(command.clone()),
+                RecordedInput::Metar { metar, .. } => {
+                    game.set_metar(metar).map_err(|e| ReplayError::BadMetar {
+                        metar: metar.clone(),
+                        message: e.to_string(),
+                    })?;
+                }
+                RecordedInput::Emergency { plane, kind, .. } => {
+                    game.declare_emergency(plane, *kind).map_err(|e| ReplayError::BadEmergency {
+                        plane: plane.clone(),
+                        message: e.to_string(),
+                    })?;
+                }
+            }
+        }
+        advance_to(&mut game, self.end_tick);
+
+        Ok(ReplayOutcome {
+            recorded_score: self.final_score,
+            replayed_score: game.score,
+            replayed_tick: game.tick(),
+        })
+    }
+}
+
+/// Run ticks until the game clock reaches `tick` or the game ends
+fn advance_to(game: &mut Game, tick: u64) {
+    while game.tick() < tick && !game.is_game_over() {
+        game.update();
+    }
+    // Nobody is watching a replay; don't let events pile up
+    game.drain_events();
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplayError::Malformed { line, message } => write!(f, "line {}: {}", line, message),
+            ReplayError::MissingHeader => write!(f, "no header line with the session seed"),
+            ReplayError::MissingEnd => write!(f, "no end line with the final score"),
+            ReplayError::WrongAirport { recorded, given } => write!(
+                f,
+                "recorded at '{}' but replayed at '{}' (pass the matching --airport)",
+                recorded, given
+            ),
+            ReplayError::WrongAircraft { recorded, given } => write!(
+                f,
+                "recorded with aircraft table {} but replayed with {} (pass the matching --aircraft)",
+                recorded, given
+            ),
+            ReplayError::BadMetar { metar, message } => write!(f, "bad METAR '{}': {}", metar, message),
+            ReplayError::BadEmergency { plane, message } => {
+                write!(f, "emergency for {} could not be declared: {}", plane, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::navdata::NavDatabase;
+
+    /// A short session with a command, a METAR and an emergency in it
+    fn play(seed: u64) -> Game {
+        let mut game = Game::with_seed(seed);
+        for _ in 0..25 {
+            game.update();
+        }
+        game.process_command("wait".to_string());
+        game.set_metar("KPDX 181853Z 18025G35KT 2SM RA OVC008").unwrap();
+        let airborne = game
+            .planes
+            .iter()
+            .find(|p| EmergencyKind::Medical.can_happen(p.status))
+            .map(|p| p.id.clone());
+        if let Some(plane) = airborne {
+            game.declare_emergency(&plane, EmergencyKind::Medical).unwrap();
+        }
+        for _ in 0..10 {
+            game.update();
+        }
+        game
+    }
+
+    #[test]
+    fn round_trips_through_jsonl() {
+        let replay = Replay::record(&play(7));
+        assert_eq!(replay.inputs.len(), 3);
+        let text = replay.to_jsonl();
+        assert_eq!(text.lines().count(), replay.inputs.len() + 2);
+        assert_eq!(Replay::from_jsonl(&text), Ok(replay));
+    }
+
+    #[test]
+    fn same_seed_and_inputs_replay_the_same_session() {
+        for seed in [1, 7, 42] {
+            let game = play(seed);
+            let replay = Replay::record(&game);
+            let outcome = replay.run(Airport::new(), AircraftDatabase::new()).unwrap();
+            assert!(outcome.matches(), "seed {}", seed);
+            assert_eq!(outcome.replayed_tick, game.tick());
+        }
+    }
+
+    #[test]
+    fn rejects_other_airports_and_aircraft() {
+        let replay = Replay::record(&play(7));
+        let east = Airport::from_toml(include_str!("../data/airports/kpdx_east.toml"), &NavDatabase::new()).unwrap();
+        assert!(matches!(
+            replay.run(east, AircraftDatabase::new()),
+            Err(ReplayError::WrongAirport { .. })
+        ));
+
+        let source = include_str!("../data/aircraft.toml");
+        let aircraft = AircraftDatabase::from_toml(&source.replacen("approach_speed_kt = 145", "approach_speed_kt = 150", 1))
+            .unwrap();
+        assert!(matches!(
+            replay.run(Airport::new(), aircraft),
+            Err(ReplayError::WrongAircraft { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_incomplete_files() {
+        let text = Replay::record(&play(7)).to_jsonl();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(
+            Replay::from_jsonl(&lines[1..].join("\n")),
+            Err(ReplayError::MissingHeader)
+        );
+        assert_eq!(
+            Replay::from_jsonl(&lines[..lines.len() - 1].join("\n")),
+            Err(ReplayError::MissingEnd)
+        );
+        assert!(matches!(
+            Replay::from_jsonl("{\"type\":\"header\"}"),
+            Err(ReplayError::Malformed { line: 1, .. })
+        ));
+    }
+}
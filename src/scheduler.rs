@@ -0,0 +1,591 @@
+use crate::aircraft::WeightClass;
+use crate::airport::{Airport, Runway, RunwayOperation};
+use crate::flight;
+use crate::ground;
+use crate::plane::{Plane, PlaneStatus};
+use crate::separation;
+use crate::sequence::{self, ArrivalSequence};
+use std::fmt;
+
+// --- SCHEDULER MODULE ---
+// The arrival and departure managers (AMAN/DMAN): plan when each arrival
+// should be cleared to land and each departure cleared for takeoff, and on
+// which runway, so nobody shares a runway or meets a wake. Arrivals keep
+// their place in the arrival sequence and go first; departures fill the gaps.
+// The plan is shown as advisories, and the delays it predicts are the
+// baseline the controller's own delays are measured against.
+//
+// The planner is a first-fit heuristic, not an optimiser. It books planes
+// one at a time in a fixed order, each into the earliest gap left by those
+// before it, and never revisits a booking. Another order or runway split can
+// have less total delay, so the baseline is a delay a controller can
+// achieve, not the least possible.
+
+/// One planned takeoff or landing
+#[derive(Debug, Clone, PartialEq)]
+pub struct Slot {
+    pub plane: String,
+    pub operation: RunwayOperation,
+    pub runway: String,
+    /// Tick the landing or takeoff clearance should be given
+    pub clearance_tick: u64,
+    /// Tick the plane touches down or rolls
+    pub movement_tick: u64,
+    /// Ticks later than the plane could go with the runways to itself
+    pub delay: u64,
+}
+
+/// A clearance the scheduler suggests giving now, worded as the command
+#[derive(Debug, Clone, PartialEq)]
+pub enum Advisory {
+    Pushback { plane: String },
+    Taxi { plane: String, runway: String },
+    Takeoff { plane: String, runway: String },
+    Land { plane: String, runway: String },
+}
+
+/// The plan for every arrival and departure in sight
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Schedule {
+    /// In clearance order
+    pub slots: Vec<Slot>,
+    /// Planes no runway can take as things stand
+    pub unplaced: Vec<String>,
+}
+
+/// Delays of the movements flown so far, planned against actual
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DelayReport {
+    pub movements: u32,
+    /// Ticks of delay the scheduler planned for them
+    pub planned_ticks: u64,
+    /// Ticks of delay they actually had
+    pub actual_ticks: u64,
+}
+
+/// Keeps the schedule up to date and measures the controller against it
+#[derive(Debug, Clone, Default)]
+pub struct Scheduler {
+    pub schedule: Schedule,
+    pub report: DelayReport,
+    /// Each plane's slot as first planned, for the delay report
+    baseline: Vec<Slot>,
+}
+
+/// A stretch of time a runway is spoken for
+#[derive(Debug, Clone, Copy)]
+struct Reservation {
+    /// From the clearance...
+    start: u64,
+    /// ...through the movement, which leaves a wake...
+    movement: u64,
+    /// ...until the runway is vacated
+    end: u64,
+    class: WeightClass,
+}
+
+/// Everything planned on one runway
+struct Timeline<'a> {
+    runway: &'a Runway,
+    reserved: Vec<Reservation>,
+}
+
+/// A plane asking for a slot
+struct Request<'a> {
+    plane: &'a Plane,
+    operation: RunwayOperation,
+    /// Earliest clearance tick on each runway it could use
+    options: Vec<(usize, u64)>,
+    /// No clearance before this, whatever the runway
+    not_before: u64,
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Scheduler::default()
+    }
+
+    /// Replan from the current traffic
+    pub fn update(&mut self, airport: &Airport, planes: &[Plane], sequence: &ArrivalSequence, tick: u64) {
+        self.schedule = Schedule::plan(airport, planes, sequence, tick);
+        self.baseline.retain(|slot| planes.iter().any(|p| p.id == slot.plane));
+        for slot in &self.schedule.slots {
+            if !self
+                .baseline
+                .iter()
+                .any(|b| b.plane == slot.plane && b.operation == slot.operation)
+            {
+                self.baseline.push(slot.clone());
+            }
+        }
+    }
+
+    /// Note that `plane` landed or took off at `tick`, and count its delay
+    pub fn record_movement(&mut self, plane: &str, operation: RunwayOperation, tick: u64) {
+        let Some(at) = self
+            .baseline
+            .iter()
+            .position(|b| b.plane == plane && b.operation == operation)
+        else {
+            return;
+        };
+        let slot = self.baseline.remove(at);
+        let earliest = slot.movement_tick - slot.delay;
+        self.report.movements += 1;
+        self.report.planned_ticks += slot.delay;
+        self.report.actual_ticks += tick.saturating_sub(earliest);
+    }
+}
+
+impl Schedule {
+    /// Plan every movement from `tick` on. Planes already on final keep
+    /// their landing; planes lined up go next; then arrivals in sequence
+    /// order, then departures by when they can reach a runway. Each takes
+    /// the earliest gap on any runway it can use, first fit.
+    pub fn plan(airport: &Airport, planes: &[Plane], sequence: &ArrivalSequence, tick: u64) -> Schedule {
+        let mut timelines: Vec<Timeline> = airport.runways.iter().map(|r| Timeline::new(r, tick)).collect();
+        let mut schedule = Schedule::default();
+
+        for plane in planes.iter().filter(|p| p.status == PlaneStatus::OnFinal) {
+            if let Some(timeline) = timelines
+                .iter_mut()
+                .find(|t| plane.runway.as_deref() == Some(t.runway.id.as_str()))
+            {
+                let movement = tick + plane.timer.max(0) as u64;
+                timeline.reserve(tick, movement, RunwayOperation::Landing, plane.weight_class());
+            }
+        }
+
+        for plane in planes.iter().filter(|p| p.status == PlaneStatus::LinedUpAndWaiting) {
+            let lined_up = timelines.iter().position(|t| t.runway.is_lined_up(&plane.id));
+            let request = Request::fixed(plane, RunwayOperation::Takeoff, lined_up, tick);
+            schedule.place(&mut timelines, airport, request);
+        }
+        let mut arrivals_from = tick;
+        for plane in sequence.order().iter().filter_map(|id| planes.iter().find(|p| &p.id == id)) {
+            if plane.status == PlaneStatus::OnFinal {
+                continue;
+            }
+            let to_final = sequence::earliest_touchdown(plane).saturating_sub(flight::FINAL_APPROACH_TICKS as u64);
+            let mut request = Request::any(plane, RunwayOperation::Landing, &timelines, |_| Some(tick + to_final));
+            // Keep the sequence: nobody is cleared before the plane ahead
+            request.not_before = arrivals_from;
+            arrivals_from = schedule.place(&mut timelines, airport, request).unwrap_or(arrivals_from);
+        }
+        let mut departures: Vec<Request> = planes
+            .iter()
+            .filter_map(|plane| departure_request(plane, airport, &timelines, tick))
+            .collect();
+        departures.sort_by_key(|r| r.options.iter().map(|&(_, at)| at).min());
+        for request in departures {
+            schedule.place(&mut timelines, airport, request);
+        }
+
+        schedule.slots.sort_by_key(|s| s.clearance_tick);
+        schedule
+    }
+
+    /// Book the best slot for `request`, or note that there is none.
+    /// Returns the clearance tick.
+    fn place(&mut self, timelines: &mut [Timeline], airport: &Airport, request: Request) -> Option<u64> {
+        let plane = request.plane;
+        let usable = |runway: &Runway| {
+            runway.is_open && plane.check_runway_suits(runway, &airport.weather, request.operation).is_ok()
+        };
+        let best = request
+            .options
+            .iter()
+            .filter(|&&(at, _)| usable(timelines[at].runway))
+            .map(|&(at, earliest)| {
+                let from = earliest.max(request.not_before);
+                let clearance = timelines[at].earliest_gap(from, request.operation, plane.weight_class());
+                (clearance, at, earliest)
+            })
+            .min_by_key(|&(clearance, at, _)| (clearance, at));
+        let Some((clearance, at, earliest)) = best else {
+            self.unplaced.push(plane.id.clone());
+            return None;
+        };
+        let movement = clearance + lead(request.operation);
+        timelines[at].reserve(clearance, movement, request.operation, plane.weight_class());
+        self.slots.push(Slot {
+            plane: plane.id.clone(),
+            operation: request.operation,
+            runway: timelines[at].runway.id.clone(),
+            clearance_tick: clearance,
+            movement_tick: movement,
+            delay: clearance - earliest,
+        });
+        Some(clearance)
+    }
+
+    pub fn slot(&self, plane: &str) -> Option<&Slot> {
+        self.slots.iter().find(|s| s.plane == plane)
+    }
+
+    /// Ticks of delay across every planned movement
+    pub fn total_delay(&self) -> u64 {
+        self.slots.iter().map(|s| s.delay).sum()
+    }
+
+    /// What to clear now to keep to the plan: landings and takeoffs whose
+    /// slot has come, taxi for departures on the ramp, and pushback for
+    /// departures that would otherwise miss their slot
+    pub fn advisories(&self, planes: &[Plane], tick: u64) -> Vec<Advisory> {
+        let mut advisories = Vec::new();
+        for slot in &self.slots {
+            let Some(plane) = planes.iter().find(|p| p.id == slot.plane) else {
+                continue;
+            };
+            let (id, runway) = (plane.id.clone(), slot.runway.clone());
+            let due = slot.clearance_tick <= tick;
+            match plane.status {
+                PlaneStatus::ReadyToLand if due => advisories.push(Advisory::Land { plane: id, runway }),
+                PlaneStatus::ReadyForTakeoff | PlaneStatus::LinedUpAndWaiting if due => {
+                    advisories.push(Advisory::Takeoff { plane: id, runway })
+                }
+                PlaneStatus::ReadyToTaxi => advisories.push(Advisory::Taxi { plane: id, runway }),
+                PlaneStatus::ReadyForPushback if slot.delay == 0 => advisories.push(Advisory::Pushback { plane: id }),
+                _ => {}
+            }
+        }
+        advisories
+    }
+}
+
+impl<'a> Timeline<'a> {
+    /// What `runway` is already committed to at `tick`: the wake of its
+    /// last movement, and whoever is on it
+    fn new(runway: &'a Runway, tick: u64) -> Self {
+        let mut reserved = Vec::new();
+        if let Some(last) = &runway.last_movement {
+            reserved.push(Reservation {
+                start: last.tick,
+                movement: last.tick,
+                end: last.tick + separation::occupancy_ticks(last.operation),
+                class: last.weight_class,
+            });
+        }
+        // A vehicle, a plane being towed off or one crossing; no wake
+        let held_until = runway
+            .occupancy
+            .as_ref()
+            .and_then(|o| o.until)
+            .filter(|&until| until > tick)
+            .or(runway.crossing.as_ref().map(|_| tick + 1));
+        if let Some(end) = held_until {
+            reserved.push(Reservation {
+                start: tick,
+                movement: tick,
+                end,
+                class: WeightClass::Light,
+            });
+        }
+        Timeline { runway, reserved }
+    }
+
+    /// Earliest clearance tick from `from` that fits between the movements
+    /// already planned, clear of their occupancy and wake
+    fn earliest_gap(&self, from: u64, operation: RunwayOperation, class: WeightClass) -> u64 {
+        let mut clearance = from;
+        while let Some(conflict) = self.reserved.iter().find(|r| !fits(r, clearance, operation, class)) {
+            clearance = conflict.end.max(conflict.movement + separation::wake_ticks(conflict.class, class));
+        }
+        clearance
+    }
+
+    /// Book a movement cleared at `clearance`
+    fn reserve(&mut self, clearance: u64, movement: u64, operation: RunwayOperation, class: WeightClass) {
+        self.reserved.push(Reservation {
+            start: clearance,
+            movement,
+            end: movement + separation::occupancy_ticks(operation),
+            class,
+        });
+    }
+}
+
+impl<'a> Request<'a> {
+    /// A plane that can only use one runway, from `tick`
+    fn fixed(plane: &'a Plane, operation: RunwayOperation, runway: Option<usize>, tick: u64) -> Self {
+        Request {
+            plane,
+            operation,
+            options: runway.map(|at| (at, tick)).into_iter().collect(),
+            not_before: 0,
+        }
+    }
+
+    /// A plane that could use any runway `earliest` gives a time for
+    fn any(
+        plane: &'a Plane,
+        operation: RunwayOperation,
+        timelines: &[Timeline],
+        earliest: impl Fn(&Runway) -> Option<u64>,
+    ) -> Self {
+        let options = timelines
+            .iter()
+            .enumerate()
+            .filter_map(|(at, t)| Some((at, earliest(t.runway)?)))
+            .collect();
+        Request {
+            plane,
+            operation,
+            options,
+            not_before: 0,
+        }
+    }
+}
+
+/// Slot request for a departure not yet lined up: at the runway it is
+/// holding short of or taxiing to, or any runway it could taxi to from the
+/// ramp or the gate
+fn departure_request<'a>(
+    plane: &'a Plane,
+    airport: &Airport,
+    timelines: &[Timeline],
+    tick: u64,
+) -> Option<Request<'a>> {
+    let layout = &airport.ground;
+    let position = plane.ground.as_ref()?;
+    let operation = RunwayOperation::Takeoff;
+    match plane.status {
+        PlaneStatus::ReadyForTakeoff | PlaneStatus::TaxiingToRunway => {
+            let runway = layout.node(position.destination())?.runway.as_deref()?;
+            let at = timelines.iter().position(|t| t.runway.id == runway);
+            let earliest = tick + taxi_ticks(position.remaining_ft(layout));
+            Some(Request::fixed(plane, operation, at, earliest))
+        }
+        PlaneStatus::ReadyToTaxi | PlaneStatus::ReadyForPushback => {
+            // Pushing back takes a tick before the taxi can start
+            let pushback = u64::from(plane.status == PlaneStatus::ReadyForPushback);
+            Some(Request::any(plane, operation, timelines, |runway| {
+                let holding_points: Vec<&str> = layout.holding_points(&runway.id).map(|n| n.id.as_str()).collect();
+                let route = layout.route(position.route_origin(), &holding_points, &[])?;
+                Some(tick + pushback + taxi_ticks(route.length_ft))
+            }))
+        }
+        _ => None,
+    }
+}
+
+/// Whether a movement cleared at `clearance` stays clear of `reserved`:
+/// wholly after it and its wake, or wholly before it with its own wake
+/// gone by the time `reserved` is cleared
+fn fits(reserved: &Reservation, clearance: u64, operation: RunwayOperation, class: WeightClass) -> bool {
+    let movement = clearance + lead(operation);
+    let end = movement + separation::occupancy_ticks(operation);
+    let after =
+        clearance >= reserved.end && clearance >= reserved.movement + separation::wake_ticks(reserved.class, class);
+    let before = end <= reserved.start && movement + separation::wake_ticks(class, reserved.class) <= reserved.start;
+    after || before
+}
+
+/// Ticks from clearance to movement: a landing flies its final first
+fn lead(operation: RunwayOperation) -> u64 {
+    match operation {
+        RunwayOperation::Landing => flight::FINAL_APPROACH_TICKS as u64,
+        RunwayOperation::Takeoff => 0,
+    }
+}
+
+fn taxi_ticks(distance_ft: f64) -> u64 {
+    (distance_ft / ground::TAXI_FT_PER_TICK).ceil() as u64
+}
+
+impl fmt::Display for Slot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.operation {
+            RunwayOperation::Landing => write!(
+                f,
+                "tick {}: land {} on {}, touchdown tick {} (delay +{})",
+                self.clearance_tick, self.plane, self.runway, self.movement_tick, self.delay
+            ),
+            RunwayOperation::Takeoff => write!(
+                f,
+                "tick {}: takeoff {} from {} (delay +{})",
+                self.clearance_tick, self.plane, self.runway, self.delay
+            ),
+        }
+    }
+}
+
+impl fmt::Display for Advisory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Advisory::Pushback { plane } => write!(f, "pushback {}", plane),
+            Advisory::Taxi { plane, runway } => write!(f, "taxi {} {}", plane, runway),
+            Advisory::Takeoff { plane, runway } => write!(f, "takeoff {} {}", plane, runway),
+            Advisory::Land { plane, runway } => write!(f, "land {} {}", plane, runway),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aircraft::AircraftDatabase;
+    use crate::flight::FlightState;
+    use crate::flight_plan::FlightPlanManager;
+    use crate::ground::GroundPosition;
+    use crate::navdata::NavDatabase;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    /// The classic layout in calm, clear weather
+    fn calm_airport() -> Airport {
+        let mut airport = Airport::new();
+        airport.set_metar("KPDX 181853Z 00000KT 10SM CLR", 0, &mut Vec::new()).unwrap();
+        airport
+    }
+
+    struct Traffic {
+        manager: FlightPlanManager,
+        navdata: NavDatabase,
+        aircraft: AircraftDatabase,
+        rng: StdRng,
+        planes: Vec<Plane>,
+    }
+
+    impl Traffic {
+        fn new() -> Self {
+            Traffic {
+                manager: FlightPlanManager::new(),
+                navdata: NavDatabase::new(),
+                aircraft: AircraftDatabase::new(),
+                rng: StdRng::seed_from_u64(7),
+                planes: Vec::new(),
+            }
+        }
+
+        fn plane(&mut self, aircraft_type: &str, arriving: bool) -> Plane {
+            let plan = self
+                .manager
+                .generate_new_plan(&[aircraft_type], &self.navdata, "KPDX", arriving, &mut self.rng);
+            Plane::new(plan.unwrap(), &self.aircraft, &mut self.rng)
+        }
+
+        /// An arrival circling near the field, asking to land
+        fn arrival(&mut self, aircraft_type: &str) -> String {
+            let mut plane = self.plane(aircraft_type, true);
+            plane.status = PlaneStatus::ReadyToLand;
+            plane.flight = Some(FlightState::arriving(90.0, 8.0, 3000.0, 180.0));
+            let id = plane.id.clone();
+            self.planes.push(plane);
+            id
+        }
+
+        /// A departure at a holding point, ready for takeoff
+        fn departure(&mut self, aircraft_type: &str, holding_point: &str) -> String {
+            let mut plane = self.plane(aircraft_type, false);
+            plane.status = PlaneStatus::ReadyForTakeoff;
+            plane.ground = Some(GroundPosition::at(holding_point));
+            let id = plane.id.clone();
+            self.planes.push(plane);
+            id
+        }
+
+        fn class(&self, id: &str) -> WeightClass {
+            self.planes.iter().find(|p| p.id == id).unwrap().weight_class()
+        }
+
+        fn sequence(&self) -> ArrivalSequence {
+            let mut sequence = ArrivalSequence::new();
+            sequence.update(&self.planes);
+            sequence
+        }
+    }
+
+    #[test]
+    fn slots_on_a_runway_keep_clear_of_occupancy_and_wake() {
+        let mut traffic = Traffic::new();
+        for aircraft_type in ["B787", "E175", "A330", "B737", "E175"] {
+            traffic.arrival(aircraft_type);
+        }
+        for aircraft_type in ["B787", "E175", "A320"] {
+            traffic.departure(aircraft_type, "HS-24L");
+        }
+        traffic.departure("E175", "HS-24R");
+        let sequence = traffic.sequence();
+        let schedule = Schedule::plan(&calm_airport(), &traffic.planes, &sequence, 100);
+
+        assert!(schedule.unplaced.is_empty(), "{:?}", schedule.unplaced);
+        assert_eq!(schedule.slots.len(), traffic.planes.len());
+        for (i, a) in schedule.slots.iter().enumerate() {
+            assert_eq!(a.movement_tick, a.clearance_tick + lead(a.operation));
+            for b in schedule.slots[i + 1..].iter().filter(|b| b.runway == a.runway) {
+                let (first, second) = if a.movement_tick <= b.movement_tick { (a, b) } else { (b, a) };
+                let clear = first.movement_tick + separation::occupancy_ticks(first.operation);
+                let wake = first.movement_tick
+                    + separation::wake_ticks(traffic.class(&first.plane), traffic.class(&second.plane));
+                assert!(second.clearance_tick >= clear, "{} cleared onto {}'s runway", second, first);
+                assert!(second.movement_tick >= wake, "{} moves in {}'s wake", second, first);
+            }
+        }
+
+        // Arrivals are cleared in sequence order
+        let cleared: Vec<u64> = sequence
+            .order()
+            .iter()
+            .map(|id| schedule.slot(id).unwrap().clearance_tick)
+            .collect();
+        assert!(cleared.windows(2).all(|pair| pair[0] <= pair[1]), "{:?}", cleared);
+    }
+
+    #[test]
+    fn a_light_departure_waits_out_a_heavy_wake() {
+        let mut traffic = Traffic::new();
+        let heavy = traffic.departure("B787", "HS-24L");
+        let light = traffic.departure("E175", "HS-24L");
+        let schedule = Schedule::plan(&calm_airport(), &traffic.planes, &ArrivalSequence::new(), 10);
+
+        let heavy = schedule.slot(&heavy).unwrap();
+        let light = schedule.slot(&light).unwrap();
+        assert_eq!((heavy.clearance_tick, heavy.delay), (10, 0));
+        let wait = separation::wake_ticks(WeightClass::Heavy, WeightClass::Light);
+        assert_eq!((light.clearance_tick, light.delay), (10 + wait, wait));
+        assert_eq!(schedule.total_delay(), wait);
+    }
+
+    #[test]
+    fn the_delay_report_adds_up_planned_and_actual_delay() {
+        let mut traffic = Traffic::new();
+        let heavy = traffic.departure("B787", "HS-24L");
+        let light = traffic.departure("E175", "HS-24L");
+        let airport = calm_airport();
+        let mut scheduler = Scheduler::new();
+        scheduler.update(&airport, &traffic.planes, &ArrivalSequence::new(), 10);
+        let planned = scheduler.schedule.slot(&light).unwrap().delay;
+
+        // The heavy goes two ticks late; the light exactly on its slot
+        scheduler.record_movement(&heavy, RunwayOperation::Takeoff, 12);
+        scheduler.record_movement(&light, RunwayOperation::Takeoff, 10 + planned);
+        assert_eq!(
+            scheduler.report,
+            DelayReport {
+                movements: 2,
+                planned_ticks: planned,
+                actual_ticks: 2 + planned,
+            }
+        );
+
+        // A movement counts once, and unplanned ones not at all
+        scheduler.record_movement(&heavy, RunwayOperation::Takeoff, 20);
+        scheduler.record_movement(&light, RunwayOperation::Landing, 20);
+        scheduler.record_movement("NOBODY", RunwayOperation::Takeoff, 20);
+        assert_eq!(scheduler.report.movements, 2);
+        assert_eq!(scheduler.report.actual_ticks, 2 + planned);
+    }
+
+    #[test]
+    fn a_movement_ahead_of_its_slot_counts_no_delay() {
+        let mut traffic = Traffic::new();
+        traffic.departure("B787", "HS-24L");
+        let light = traffic.departure("E175", "HS-24L");
+        let mut scheduler = Scheduler::new();
+        scheduler.update(&calm_airport(), &traffic.planes, &ArrivalSequence::new(), 10);
+        scheduler.record_movement(&light, RunwayOperation::Takeoff, 10);
+        assert_eq!(scheduler.report.actual_ticks, 0);
+        assert_eq!(scheduler.report.planned_ticks, scheduler.schedule.slot(&light).unwrap().delay);
+    }
+}
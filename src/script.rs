@@ -0,0 +1,200 @@
+use crate::emergency::EmergencyKind;
+use crate::event::SimEvent;
+use crate::game::Game;
+use crate::metar::Metar;
+use std::fmt;
+
+// --- SCRIPT MODULE ---
+// Runs a list of commands against a Game without a terminal: no sleeping,
+// no screen clearing. Used for regression-testing scoring rules and tutorial
+// scenarios.
+//
+// Script format, one entry per line:
+//   pushback SWA123     a command, issued and then followed by one tick
+//   @tick 40            let the game run idle until it has simulated 40 ticks
+//   @metar KPDX 181853Z 24015G25KT 3SM RA BKN008
+//                       set the airport weather from a METAR
+//   @emergency DAL456 engine_failure
+//                       the crew declares an emergency (engine_failure,
+//                       medical, bird_strike, hydraulic_failure, diversion)
+//   # comment           blank lines and lines starting with '#' are ignored
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScriptStep {
+    /// A command line, passed to Game::process_command as-is
+    Command(String),
+    /// Run idle ticks until the game clock reaches this tick
+    WaitUntil(u64),
+    /// Set the weather from this METAR (already checked to parse)
+    SetMetar(String),
+    /// Declare an emergency on a plane
+    Emergency { plane: String, kind: EmergencyKind },
+}
+
+/// A script line that could not be understood
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptError {
+    pub line: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Script {
+    pub steps: Vec<ScriptStep>,
+}
+
+impl Script {
+    pub fn parse(source: &str) -> Result<Script, ScriptError> {
+        let mut steps = Vec::new();
+
+        for (index, raw) in source.lines().enumerate() {
+            let line = raw.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            match line.strip_prefix('@') {
+                Some(marker) => steps.push(parse_marker(marker).map_err(|message| {
+                    ScriptError {
+                        line: index + 1,
+                        message,
+                    }
+                })?),
+                None => steps.push(ScriptStep::Command(line.to_string())),
+            }
+        }
+
+        Ok(Script { steps })
+    }
+
+    /// Run every step against `game`, handing each event to `on_event` as it
+    /// is produced. Stops early if the game ends. A `@tick` marker that is
+    /// already in the past is a no-op.
+    pub fn run(&self, game: &mut Game, mut on_event: impl FnMut(u64, SimEvent)) {
+        for step in &self.steps {
+            if game.is_game_over() {
+                break;
+            }
+            match step {
+                ScriptStep::Command(line) => {
+                    game.process_command(line.clone());
+                    flush(game, &mut on_event);
+                    game.update();
+                    flush(game, &mut on_event);
+                }
+                ScriptStep::SetMetar(text) => {
+                    // Checked when the script was parsed
+                    game.set_metar(text).expect("script METAR parses");
+                    flush(game, &mut on_event);
+                }
+                ScriptStep::Emergency { plane, kind } => {
+                    // The plane may not exist, or be in the wrong phase of
+                    // flight, when the script gets here
+                    if let Err(e) = game.declare_emergency(plane, *kind) {
+                        on_event(game.tick(), SimEvent::Info(format!("@emergency ignored: {}", e)));
+                    }
+                    flush(game, &mut on_event);
+                }
+                ScriptStep::WaitUntil(tick) => {
+                    while game.tick() < *tick && !game.is_game_over() {
+                        game.update();
+                        flush(game, &mut on_event);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// `@tick N`, `@metar <report>` or `@emergency <plane> <kind>`, without the '@'
+fn parse_marker(marker: &str) -> Result<ScriptStep, String> {
+    let (name, rest) = marker.split_once(char::is_whitespace).unwrap_or((marker, ""));
+    let rest = rest.trim();
+    match name {
+        "tick" => rest
+            .parse()
+            .map(ScriptStep::WaitUntil)
+            .map_err(|_| format!("expected '@tick N', found '@{}'", marker)),
+        "metar" => match Metar::parse(rest) {
+            Ok(_) => Ok(ScriptStep::SetMetar(rest.to_string())),
+            Err(e) => Err(format!("bad METAR: {}", e)),
+        },
+        "emergency" => match rest.split_whitespace().collect::<Vec<_>>()[..] {
+            [plane, kind] => kind
+                .parse()
+                .map(|kind| ScriptStep::Emergency {
+                    plane: plane.to_uppercase(),
+                    kind,
+                })
+                .map_err(|e| e.to_string()),
+            _ => Err(format!("expected '@emergency PLANE KIND', found '@{}'", marker)),
+        },
+        _ => Err(format!("unknown marker '@{}'", name)),
+    }
+}
+
+/// Hand every pending event to the caller, tagged with the current tick
+fn flush(game: &mut Game, on_event: &mut impl FnMut(u64, SimEvent)) {
+    let tick = game.tick();
+    for event in game.drain_events() {
+        on_event(tick, event);
+    }
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_commands_markers_and_comments() {
+        let script = Script::parse(
+            "# depart the first plane\n\
+             \n\
+             @metar KPDX 181853Z 24015G25KT 3SM RA BKN008\n\
+             @tick 20\n\
+             \x20 pushback SWA123  \n\
+             @emergency swa123 bird_strike\n",
+        )
+        .unwrap();
+        assert_eq!(
+            script.steps,
+            vec![
+                ScriptStep::SetMetar("KPDX 181853Z 24015G25KT 3SM RA BKN008".to_string()),
+                ScriptStep::WaitUntil(20),
+                ScriptStep::Command("pushback SWA123".to_string()),
+                ScriptStep::Emergency {
+                    plane: "SWA123".to_string(),
+                    kind: EmergencyKind::BirdStrike,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_the_line_of_a_bad_marker() {
+        for (source, line) in [
+            ("wait\n@tick soon", 2),
+            ("@metar KPDX", 1),
+            ("\n\n@emergency SWA123", 3),
+            ("@emergency SWA123 alien_abduction", 1),
+            ("# comment\n@fly SWA123", 2),
+        ] {
+            let error = Script::parse(source).unwrap_err();
+            assert_eq!(error.line, line, "{}", source);
+        }
+    }
+
+    #[test]
+    fn leaves_command_syntax_to_the_game() {
+        let script = Script::parse("fly me to the moon").unwrap();
+        assert_eq!(script.steps, vec![ScriptStep::Command("fly me to the moon".to_string())]);
+    }
+}
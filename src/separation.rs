@@ -0,0 +1,178 @@
+use crate::airport::RunwayOperation;
+use crate::flight::Position;
+use crate::aircraft::WeightClass;
+use std::fmt;
+
+// --- SEPARATION MODULE ---
+// Minimum spacing between aircraft: radar separation in the air, wake
+// turbulence spacing between runway movements, and how long a movement keeps
+// the runway occupied, and how serious a runway incursion was.
+
+/// Planes closer than this horizontally...
+pub const RADAR_SEPARATION_NM: f64 = 3.0;
+/// ...must be at least this far apart vertically
+pub const VERTICAL_SEPARATION_FT: f64 = 1000.0;
+/// Closer than this in both planes is a collision
+pub const COLLISION_NM: f64 = 0.1;
+pub const COLLISION_FT: f64 = 200.0;
+
+/// Ticks a movement keeps the runway occupied: a landing has to roll out
+/// and vacate, a departure is airborne within the tick
+pub fn occupancy_ticks(operation: RunwayOperation) -> u64 {
+    match operation {
+        RunwayOperation::Landing => 2,
+        RunwayOperation::Takeoff => 1,
+    }
+}
+
+/// Ticks a follower must wait after a leader uses the same runway, so the
+/// leader's wake has time to decay
+pub fn wake_ticks(leader: WeightClass, follower: WeightClass) -> u64 {
+    match (leader, follower) {
+        (WeightClass::Heavy, WeightClass::Heavy) => 2,
+        (WeightClass::Heavy, _) => 3,
+        (WeightClass::Medium, WeightClass::Light) => 3,
+        _ => 0,
+    }
+}
+
+/// How serious a runway incursion was, by what else was on the runway:
+/// A a landing, B a departure, C a plane lined up, D nobody
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IncursionSeverity {
+    A,
+    B,
+    C,
+    D,
+}
+
+impl IncursionSeverity {
+    pub const ALL: [IncursionSeverity; 4] = [
+        IncursionSeverity::A,
+        IncursionSeverity::B,
+        IncursionSeverity::C,
+        IncursionSeverity::D,
+    ];
+}
+
+impl fmt::Display for IncursionSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// How close two planes came to each other
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Proximity {
+    pub horizontal_nm: f64,
+    pub vertical_ft: f64,
+}
+
+impl Proximity {
+    pub fn is_loss_of_separation(&self) -> bool {
+        self.horizontal_nm < RADAR_SEPARATION_NM && self.vertical_ft < VERTICAL_SEPARATION_FT
+    }
+
+    pub fn is_collision(&self) -> bool {
+        self.horizontal_nm < COLLISION_NM && self.vertical_ft < COLLISION_FT
+    }
+}
+
+/// Closest approach of two planes over a tick, assuming each flew straight
+/// from where it was (`None` if it wasn't airborne yet) to where it is now.
+/// A tick is long enough for planes to pass through each other, so checking
+/// only the end positions would miss conflicts.
+pub fn closest_approach(
+    a: (Option<Position>, Position),
+    b: (Option<Position>, Position),
+) -> Proximity {
+    let (a0, a1) = (a.0.unwrap_or(a.1), a.1);
+    let (b0, b1) = (b.0.unwrap_or(b.1), b.1);
+
+    // Relative motion of b with respect to a
+    let (rx, ry, rz) = (b0.x_nm - a0.x_nm, b0.y_nm - a0.y_nm, b0.altitude_ft - a0.altitude_ft);
+    let (vx, vy, vz) = (
+        (b1.x_nm - b0.x_nm) - (a1.x_nm - a0.x_nm),
+        (b1.y_nm - b0.y_nm) - (a1.y_nm - a0.y_nm),
+        (b1.altitude_ft - b0.altitude_ft) - (a1.altitude_ft - a0.altitude_ft),
+    );
+
+    // Minimise horizontal distance; take the vertical gap at that moment
+    let speed_squared = vx * vx + vy * vy;
+    let t = if speed_squared > 0.0 {
+        (-(rx * vx + ry * vy) / speed_squared).clamp(0.0, 1.0)
+    } else {
+        1.0
+    };
+    Proximity {
+        horizontal_nm: (rx + vx * t).hypot(ry + vy * t),
+        vertical_ft: (rz + vz * t).abs(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(x_nm: f64, y_nm: f64, altitude_ft: f64) -> Position {
+        Position {
+            x_nm,
+            y_nm,
+            altitude_ft,
+        }
+    }
+
+    #[test]
+    fn catches_planes_passing_through_each_other_within_a_tick() {
+        // Head-on along the x axis: both ends are 4 NM apart, but they
+        // pass level halfway through the tick
+        let proximity = closest_approach(
+            (Some(at(-2.0, 0.0, 5000.0)), at(2.0, 0.0, 5000.0)),
+            (Some(at(2.0, 0.0, 5000.0)), at(-2.0, 0.0, 5000.0)),
+        );
+        assert!(proximity.horizontal_nm < 1e-9);
+        assert!(proximity.vertical_ft < 1e-9);
+    }
+
+    #[test]
+    fn takes_the_vertical_gap_at_the_closest_point() {
+        // b descends 2000 ft while crossing a's track
+        let proximity = closest_approach(
+            (Some(at(0.0, 0.0, 4000.0)), at(0.0, 0.0, 4000.0)),
+            (Some(at(-1.0, 1.0, 6000.0)), at(1.0, 1.0, 4000.0)),
+        );
+        assert!((proximity.horizontal_nm - 1.0).abs() < 1e-9);
+        assert!((proximity.vertical_ft - 1000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn measures_from_the_end_when_the_closest_point_is_outside_the_tick() {
+        // Diverging planes were closest at the start of the tick
+        let diverging = closest_approach(
+            (Some(at(0.0, 0.0, 3000.0)), at(0.0, -1.0, 3000.0)),
+            (Some(at(0.0, 1.0, 3000.0)), at(0.0, 2.0, 3000.0)),
+        );
+        assert!((diverging.horizontal_nm - 1.0).abs() < 1e-9);
+
+        // A plane that was not airborne yet is measured where it is now
+        let just_airborne = closest_approach(
+            (None, at(0.0, 0.0, 500.0)),
+            (Some(at(0.0, 4.0, 3000.0)), at(0.0, 3.0, 3000.0)),
+        );
+        assert!((just_airborne.horizontal_nm - 3.0).abs() < 1e-9);
+        assert!((just_airborne.vertical_ft - 2500.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn wake_spacing_protects_lighter_followers() {
+        use WeightClass::*;
+        assert_eq!(wake_ticks(Heavy, Heavy), 2);
+        assert_eq!(wake_ticks(Heavy, Medium), 3);
+        assert_eq!(wake_ticks(Heavy, Light), 3);
+        assert_eq!(wake_ticks(Medium, Light), 3);
+        assert_eq!(wake_ticks(Medium, Medium), 0);
+        assert_eq!(wake_ticks(Medium, Heavy), 0);
+        assert_eq!(wake_ticks(Light, Heavy), 0);
+        assert_eq!(wake_ticks(Light, Light), 0);
+    }
+}
@@ -0,0 +1,146 @@
+use crate::airport::RunwayOperation;
+use crate::flight;
+use crate::plane::{Plane, PlaneStatus};
+use crate::separation;
+
+// --- SEQUENCE MODULE ---
+// The arrival manager: the order arrivals are to land in, and when each can
+// expect to touch down. New arrivals slot in by their own estimate, planes
+// on final and emergencies go to the front, and the controller can reorder
+// the rest with `sequence`.
+
+/// Arrivals waiting to land, first to land first
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ArrivalSequence {
+    order: Vec<String>,
+}
+
+/// When one arrival in the sequence can expect to touch down
+#[derive(Debug, Clone, PartialEq)]
+pub struct Estimate {
+    pub plane: String,
+    pub tick: u64,
+}
+
+impl ArrivalSequence {
+    pub fn new() -> Self {
+        ArrivalSequence::default()
+    }
+
+    /// Callsigns in landing order
+    pub fn order(&self) -> &[String] {
+        &self.order
+    }
+
+    /// Place of `plane` in the sequence, counting from 0
+    pub fn position(&self, plane: &str) -> Option<usize> {
+        self.order.iter().position(|id| id.eq_ignore_ascii_case(plane))
+    }
+
+    /// Drop arrivals that have landed or gone, slot new ones in by their
+    /// estimate, and keep planes on final, then emergencies, at the front
+    pub fn update(&mut self, planes: &[Plane]) {
+        self.order
+            .retain(|id| planes.iter().any(|p| &p.id == id && in_sequence(p)));
+        for plane in planes.iter().filter(|p| in_sequence(p)) {
+            if self.order.contains(&plane.id) {
+                continue;
+            }
+            let own = earliest_touchdown(plane);
+            let at = self
+                .order
+                .iter()
+                .position(|id| find(planes, id).is_some_and(|p| earliest_touchdown(p) > own))
+                .unwrap_or(self.order.len());
+            self.order.insert(at, plane.id.clone());
+        }
+        // Stable, so the controller's order holds within each group
+        self.order.sort_by_key(|id| {
+            let plane = find(planes, id);
+            (
+                plane.is_none_or(|p| p.status != PlaneStatus::OnFinal),
+                !plane.is_some_and(Plane::has_priority),
+            )
+        });
+    }
+
+    /// Move `plane` to land straight after `leader`. Returns its new place,
+    /// counting from 1, or None if either isn't in the sequence.
+    pub fn move_after(&mut self, plane: &str, leader: &str) -> Option<usize> {
+        let from = self.position(plane)?;
+        self.position(leader)?;
+        let id = self.order.remove(from);
+        let to = self.position(leader)? + 1;
+        self.order.insert(to, id);
+        Some(to + 1)
+    }
+
+    /// A plane without priority sequenced ahead of one with it, and that
+    /// one. Planes already on final don't count; they are landing anyway.
+    pub fn priority_violation(&self, planes: &[Plane]) -> Option<(String, String)> {
+        let waiting: Vec<&Plane> = self
+            .order
+            .iter()
+            .filter_map(|id| find(planes, id))
+            .filter(|p| p.status != PlaneStatus::OnFinal)
+            .collect();
+        let first_normal = waiting.iter().position(|p| !p.has_priority())?;
+        let priority = waiting[first_normal..].iter().find(|p| p.has_priority())?;
+        Some((waiting[first_normal].id.clone(), priority.id.clone()))
+    }
+
+    /// Estimated touchdown for every arrival, in order. Each lands no sooner
+    /// than it can get there, and no sooner than the runway and the wake of
+    /// the plane ahead allow.
+    pub fn estimates(&self, planes: &[Plane], tick: u64) -> Vec<Estimate> {
+        let mut estimates = Vec::new();
+        let mut ahead: Option<(&Plane, u64)> = None;
+        for plane in self.order.iter().filter_map(|id| find(planes, id)) {
+            let mut at = tick + earliest_touchdown(plane);
+            if let Some((leader, leader_at)) = ahead {
+                let spacing = separation::occupancy_ticks(RunwayOperation::Landing)
+                    .max(separation::wake_ticks(leader.weight_class(), plane.weight_class()));
+                at = at.max(leader_at + spacing);
+            }
+            estimates.push(Estimate {
+                plane: plane.id.clone(),
+                tick: at,
+            });
+            ahead = Some((plane, at));
+        }
+        estimates
+    }
+}
+
+/// Airborne arrivals that haven't landed yet
+fn in_sequence(plane: &Plane) -> bool {
+    plane.flight.is_some()
+        && matches!(
+            plane.status,
+            PlaneStatus::InAir
+                | PlaneStatus::ReadyToLand
+                | PlaneStatus::Holding
+                | PlaneStatus::OnFinal
+                | PlaneStatus::MissedApproach
+        )
+}
+
+/// Ticks until `plane` could touch down with nobody in its way: the rest of
+/// its final, or the flight in to the approach area and then a final
+pub fn earliest_touchdown(plane: &Plane) -> u64 {
+    let final_ticks = flight::FINAL_APPROACH_TICKS as u64;
+    match (plane.status, &plane.flight) {
+        (PlaneStatus::OnFinal, _) => plane.timer.max(0) as u64,
+        (PlaneStatus::ReadyToLand, _) => final_ticks,
+        (_, Some(flight)) => {
+            let to_go_nm = (flight.range_nm() - flight::APPROACH_RANGE_NM).max(0.0);
+            let nm_per_tick = flight.ground_speed_kt.max(1.0) * flight::SECONDS_PER_TICK / 3600.0;
+            (to_go_nm / nm_per_tick).ceil() as u64 + final_ticks
+        }
+        (_, None) => final_ticks,
+    }
+}
+
+fn find<'a>(planes: &'a [Plane], id: &str) -> Option<&'a Plane> {
+    planes.iter().find(|p| p.id == id)
+}
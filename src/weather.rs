@@ -0,0 +1,303 @@
+use crate::airport::{Weather, Wind};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::VecDeque;
+
+// --- WEATHER MODULE ---
+// Weather arrives as a series of fronts. Each front sets target conditions
+// from the tick it arrives; every tick the actual weather drifts toward the
+// active front's target (wind backing or veering a few degrees, gusts
+// building, cloud lowering), so changes are gradual and can be forecast.
+
+/// How many fronts, including the active one, are kept planned ahead
+const FRONTS_PLANNED: usize = 3;
+/// Most a wind direction turns per tick, in degrees
+const TURN_PER_TICK: i32 = 3;
+/// No ceiling is treated as cloud this high when lowering or lifting it
+const NO_CEILING_FT: u32 = 12000;
+
+/// Conditions expected from `starts_at` until the next front arrives
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Front {
+    pub starts_at: u64,
+    pub weather: Weather,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct WeatherSystem {
+    /// The active front first, then the ones still to come
+    fronts: VecDeque<Front>,
+}
+
+impl WeatherSystem {
+    pub fn new() -> Self {
+        WeatherSystem::default()
+    }
+
+    /// The active front and the ones forecast to follow it
+    pub fn forecast(&self) -> impl Iterator<Item = &Front> {
+        self.fronts.iter()
+    }
+
+    /// Advance one tick: move `current` toward the active front and plan
+    /// more fronts as old ones pass. Returns the new weather.
+    pub fn step(&mut self, current: &Weather, prevailing_wind: u16, tick: u64, rng: &mut impl Rng) -> Weather {
+        while self.fronts.get(1).is_some_and(|next| next.starts_at <= tick) {
+            self.fronts.pop_front();
+        }
+        self.plan(prevailing_wind, tick, rng);
+
+        let target = &self.fronts[0].weather;
+        let mut weather = *current;
+        weather.wind = drift_wind(&current.wind, &target.wind);
+        weather.rain = target.rain;
+        weather.visibility_sm = drift_visibility(current.visibility_sm, target.visibility_sm);
+        weather.ceiling_ft = drift_ceiling(current.ceiling_ft, target.ceiling_ft);
+        weather
+    }
+
+    /// Keep `weather` as the target for at least `ticks`, pushing the rest
+    /// of the forecast back. Used when a scenario sets the weather directly.
+    pub fn hold(&mut self, weather: Weather, tick: u64, ticks: u64) {
+        let delay = match self.fronts.get(1) {
+            Some(next) => (tick + ticks).saturating_sub(next.starts_at),
+            None => 0,
+        };
+        for front in self.fronts.iter_mut().skip(1) {
+            front.starts_at += delay;
+        }
+        match self.fronts.front_mut() {
+            Some(active) => active.weather = weather,
+            None => self.fronts.push_back(Front {
+                starts_at: tick,
+                weather,
+            }),
+        }
+    }
+
+    /// Top up the queue with new fronts, each growing out of the last
+    pub fn plan(&mut self, prevailing_wind: u16, tick: u64, rng: &mut impl Rng) {
+        while self.fronts.len() < FRONTS_PLANNED {
+            let front = match self.fronts.back() {
+                Some(last) => Front {
+                    starts_at: last.starts_at + rng.gen_range(40..=120),
+                    weather: next_front(&last.weather, prevailing_wind, rng),
+                },
+                None => {
+                    // Start from a moderate breeze off the prevailing direction
+                    let mut breeze = Weather::clear();
+                    breeze.wind.direction = Some(prevailing_wind);
+                    breeze.wind.speed_kt = 10;
+                    Front {
+                        starts_at: tick,
+                        weather: next_front(&breeze, prevailing_wind, rng),
+                    }
+                }
+            };
+            self.fronts.push_back(front);
+        }
+    }
+}
+
+/// Conditions for the front after one bringing `previous`
+fn next_front(previous: &Weather, prevailing_wind: u16, rng: &mut impl Rng) -> Weather {
+    // Back or veer up to 40° from the last front, staying within 90° of the
+    // prevailing wind
+    let from = previous.wind.direction.unwrap_or(prevailing_wind);
+    let turned = from as i32 + rng.gen_range(-4..=4) * 10;
+    let off_prevailing = signed_turn(prevailing_wind as i32, turned).clamp(-90, 90);
+    let direction = normalise(prevailing_wind as i32 + off_prevailing);
+
+    let speed_kt = (previous.wind.speed_kt as i32 + rng.gen_range(-10..=10)).clamp(0, 35) as u16;
+    let gust_kt = if speed_kt >= 12 && rng.gen_bool(0.4) {
+        Some(speed_kt + rng.gen_range(5..=15))
+    } else {
+        None
+    };
+
+    // Rain bands bring low cloud and poor visibility; dry spells are mostly clear
+    let rain = rng.gen_bool(0.3);
+    let (visibility_sm, ceiling_ft) = if rain {
+        let visibility = [0.5, 1.0, 2.0, 3.0, 5.0];
+        let ceiling = [200, 400, 800, 1500, 2500];
+        (*visibility.choose(rng).unwrap(), Some(*ceiling.choose(rng).unwrap()))
+    } else {
+        let visibility = [4.0, 6.0, 10.0, 10.0];
+        let ceiling = [None, None, Some(2500), Some(5000)];
+        (*visibility.choose(rng).unwrap(), *ceiling.choose(rng).unwrap())
+    };
+
+    Weather {
+        wind: Wind {
+            direction: Some(direction),
+            speed_kt,
+            gust_kt,
+        },
+        rain,
+        visibility_sm,
+        ceiling_ft,
+    }
+}
+
+fn drift_wind(current: &Wind, target: &Wind) -> Wind {
+    // A calm or variable wind takes up the new direction straight away
+    let direction = match (current.direction, target.direction) {
+        (Some(from), Some(to)) if current.speed_kt > 0 => {
+            let turn = signed_turn(from as i32, to as i32);
+            Some(normalise(from as i32 + turn.clamp(-TURN_PER_TICK, TURN_PER_TICK)))
+        }
+        _ => target.direction,
+    };
+    let speed_kt = approach(current.speed_kt as i32, target.speed_kt as i32, 1) as u16;
+
+    // Gusts build from the mean wind and die back into it
+    let gust_kt = match (current.gust_kt, target.gust_kt) {
+        (current_gust, Some(target_gust)) => {
+            let from = current_gust.unwrap_or(speed_kt) as i32;
+            Some(approach(from, target_gust as i32, 2) as u16)
+        }
+        (Some(gust), None) => Some(gust.saturating_sub(2)),
+        (None, None) => None,
+    };
+    let gust_kt = gust_kt.filter(|gust| *gust >= speed_kt + 3);
+
+    Wind {
+        direction: direction.filter(|_| speed_kt > 0),
+        speed_kt,
+        gust_kt,
+    }
+}
+
+/// Visibility changes by about a tenth per tick, in quarter-mile steps
+fn drift_visibility(current: f64, target: f64) -> f64 {
+    let step = (current * 0.1).max(0.25);
+    let next = if (target - current).abs() <= step {
+        target
+    } else if target > current {
+        current + step
+    } else {
+        current - step
+    };
+    ((next * 4.0).round() / 4.0).max(0.25)
+}
+
+/// Cloud lowers or lifts by about a tenth of the gap per tick
+fn drift_ceiling(current: Option<u32>, target: Option<u32>) -> Option<u32> {
+    let current = current.unwrap_or(NO_CEILING_FT) as i32;
+    let target = target.unwrap_or(NO_CEILING_FT) as i32;
+    let step = ((target - current).abs() / 10).max(100);
+    let next = approach(current, target, step);
+    // Ceilings are reported in hundreds of feet
+    let next = (next / 100 * 100) as u32;
+    (next < NO_CEILING_FT).then_some(next)
+}
+
+/// Move `current` toward `target` by at most `step`
+fn approach(current: i32, target: i32, step: i32) -> i32 {
+    current + (target - current).clamp(-step, step)
+}
+
+/// Shortest turn from `from` to `to` in degrees: positive is clockwise
+/// (veering), negative anticlockwise (backing)
+fn signed_turn(from: i32, to: i32) -> i32 {
+    (to - from + 540).rem_euclid(360) - 180
+}
+
+/// Wrap a heading into 1-360
+fn normalise(direction: i32) -> u16 {
+    ((direction - 1).rem_euclid(360) + 1) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::airport::Airport;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    /// Storms at KPDX blow from the south
+    const PREVAILING: u16 = 180;
+
+    /// The runway a controller would use in `weather`: the most headwind
+    fn active_runway(airport: &Airport, weather: &Weather) -> String {
+        let headwind = |id: &str| airport.get_runway(id).unwrap().wind_components(&weather.wind).headwind;
+        let mut ids: Vec<&str> = airport.runways.iter().map(|r| r.id.as_str()).collect();
+        ids.sort_by(|a, b| headwind(b).total_cmp(&headwind(a)));
+        ids[0].to_string()
+    }
+
+    #[test]
+    fn weather_drifts_within_bounds() {
+        for seed in 0..20 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut system = WeatherSystem::new();
+            let mut weather = Weather::clear();
+            for tick in 0..1000 {
+                let next = system.step(&weather, PREVAILING, tick, &mut rng);
+                let (wind, was) = (next.wind, weather.wind);
+                assert!(wind.speed_kt <= 35, "seed {} tick {}: {:?}", seed, tick, wind);
+                assert!(wind.speed_kt.abs_diff(was.speed_kt) <= 1);
+                assert!(wind.gust_kt.is_none_or(|g| g >= wind.speed_kt + 3 && g <= 50));
+                assert_eq!(wind.direction.is_some(), wind.speed_kt > 0);
+                if let (Some(direction), Some(before)) = (wind.direction, was.direction) {
+                    assert!((1..=360).contains(&direction));
+                    assert!(signed_turn(before as i32, direction as i32).abs() <= TURN_PER_TICK);
+                }
+                assert!((0.25..=10.0).contains(&next.visibility_sm));
+                assert!(next.ceiling_ft.is_none_or(|c| c % 100 == 0 && c < NO_CEILING_FT));
+
+                let fronts: Vec<&Front> = system.forecast().collect();
+                assert_eq!(fronts.len(), FRONTS_PLANNED);
+                for pair in fronts.windows(2) {
+                    assert!((40..=120).contains(&(pair[1].starts_at - pair[0].starts_at)));
+                }
+                for front in fronts {
+                    let direction = front.weather.wind.direction.unwrap();
+                    assert!(signed_turn(PREVAILING as i32, direction as i32).abs() <= 90);
+                }
+                weather = next;
+            }
+        }
+    }
+
+    #[test]
+    fn passing_front_changes_the_active_runway() {
+        let airport = Airport::new();
+        let mut southerly = Weather::clear();
+        southerly.wind = Wind {
+            direction: Some(180),
+            speed_kt: 15,
+            gust_kt: None,
+        };
+        assert_eq!(active_runway(&airport, &southerly), "18");
+
+        // A session whose next front swings the wind round onto the 24s
+        let (mut rng, mut system) = (0..100)
+            .map(|seed| {
+                let mut rng = StdRng::seed_from_u64(seed);
+                let mut system = WeatherSystem::new();
+                system.hold(southerly, 0, 0);
+                system.plan(PREVAILING, 0, &mut rng);
+                (rng, system)
+            })
+            .find(|(_, system)| {
+                let next = system.forecast().nth(1).unwrap().weather;
+                next.wind.speed_kt >= 10 && active_runway(&airport, &next) != "18"
+            })
+            .expect("no westerly front in 100 sessions");
+        let front = *system.forecast().nth(1).unwrap();
+        let after = system.forecast().nth(2).unwrap().starts_at;
+
+        let mut weather = southerly;
+        let mut changed_at = None;
+        for tick in 1..after {
+            weather = system.step(&weather, PREVAILING, tick, &mut rng);
+            if changed_at.is_none() && active_runway(&airport, &weather) != "18" {
+                changed_at = Some(tick);
+            }
+        }
+        let changed_at = changed_at.expect("the wind never came round");
+        assert!(changed_at >= front.starts_at, "changed at {} before the front at {}", changed_at, front.starts_at);
+        assert_eq!(active_runway(&airport, &weather), active_runway(&airport, &front.weather));
+    }
+}